@@ -1,3 +1 @@
-fn main(){
-    return 
-}
\ No newline at end of file
+fn main() {}
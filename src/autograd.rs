@@ -1,13 +1,17 @@
-use ndarray::Array;
-use std::cell::RefCell;
-use std::rc::{Rc, Weak};
+use crate::tensor::{dot, dot_unwrap, logsumexp_axis, sum_to_shape};
+use ndarray::{Array, Axis};
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::rc::Rc;
+
+pub type BackwardFn = Box<dyn Fn(&Array<f64, ndarray::IxDyn>, &mut Vec<Rc<RefCell<Tensor>>>)>;
 
 #[derive(Clone)]
 pub struct Tensor {
     pub data: Array<f64, ndarray::IxDyn>,
     pub grad: Option<Array<f64, ndarray::IxDyn>>,
     pub requires_grad: bool,
-    pub creator: Option<Weak<RefCell<GraphNode>>>,
+    pub creator: Option<Rc<RefCell<GraphNode>>>, // Strong, so the graph lives as long as its output
 }
 
 impl Tensor {
@@ -24,43 +28,165 @@ impl Tensor {
         self.grad = None;
     }
 
+    /// Adds `grad` into this tensor's gradient, initializing it on first use, so tensors feeding
+    /// several ops receive the sum of every incoming contribution.
+    pub fn accumulate_grad(&mut self, grad: &Array<f64, ndarray::IxDyn>) {
+        match &mut self.grad {
+            Some(existing) => *existing += grad,
+            None => self.grad = Some(grad.clone()),
+        }
+    }
+
+    /// Sets whether this tensor requires grad. Disabling it detaches the tensor, dropping its `creator` and `grad`.
+    /// Enabling it on a non-leaf tensor only affects ops built afterwards; already-recorded ops are unchanged.
+    pub fn set_requires_grad(&mut self, value: bool) {
+        self.requires_grad = value;
+        if !value {
+            self.creator = None;
+            self.grad = None;
+        }
+    }
+
+    /// Returns a copy of the data cut off from the graph, so gradients never flow back through it.
+    /// The original tensor and its graph are left untouched.
+    pub fn detach(&self) -> Tensor {
+        Tensor::new(self.data.clone(), false)
+    }
+
+    /// Applies `f` elementwise to the data. The closure isn't differentiable, so the result doesn't require grad.
+    pub fn map<F: Fn(f64) -> f64>(&self, f: F) -> Tensor {
+        Tensor::new(self.data.mapv(f), false)
+    }
+
+    /// Applies `data -= learning_rate * grad` in place; does nothing if there is no gradient.
+    pub fn sgd_update(&mut self, learning_rate: f64) {
+        if let Some(grad) = &self.grad {
+            self.data.scaled_add(-learning_rate, grad);
+        }
+    }
+
+    /// Returns a reshaped copy of the data that isn't tracked by autograd.
+    pub fn reshape(&self, shape: &[usize]) -> Tensor {
+        let len: usize = shape.iter().product();
+        assert_eq!(len, self.data.len(), "cannot reshape {:?} into {:?}", self.data.shape(), shape);
+        let data = self.data.as_standard_layout().into_owned().into_shape(shape).unwrap();
+        Tensor::new(data, false)
+    }
+
+    /// Renders the data with `precision` decimal places, right-aligning columns for 1D and 2D tensors.
+    /// Other ranks fall back to ndarray's formatting at the same precision.
+    pub fn format(&self, precision: usize) -> String {
+        let cells: Vec<String> = self.data.iter().map(|x| format!("{:.*}", precision, x)).collect();
+        let width = cells.iter().map(|cell| cell.len()).max().unwrap_or(0);
+        let row = |cells: &[String]| cells.iter().map(|cell| format!("{:>width$}", cell, width = width)).collect::<Vec<_>>().join(", ");
+
+        match self.data.shape() {
+            [_] => format!("[{}]", row(&cells)),
+            [_, cols] if *cols > 0 => {
+                let rows: Vec<String> = cells.chunks(*cols).map(|chunk| format!("[{}]", row(chunk))).collect();
+                format!("[{}]", rows.join(",\n "))
+            }
+            _ => format!("{:.*}", precision, self.data),
+        }
+    }
+
+    /// A leaf tensor was created directly rather than produced by a recorded op.
+    pub fn is_leaf(&self) -> bool {
+        self.creator.is_none()
+    }
+
+    /// Propagates gradients from this tensor into every tensor that requires grad. Leaf gradients accumulate
+    /// across calls; intermediate gradients are recomputed, so they hold only the latest pass.
     pub fn backward(&mut self) {
+        self.run_backward(false);
+    }
+
+    /// Like `backward`, but only leaf tensors keep their gradients; intermediate grads are dropped once propagated.
+    pub fn backward_leaf_only(&mut self) {
+        self.run_backward(true);
+    }
+
+    fn run_backward(&mut self, leaf_only: bool) {
         if self.grad.is_none() {
             self.grad = Some(Array::ones(self.data.raw_dim()));
         }
 
-        let mut stack = vec![Rc::new(RefCell::new(self.clone()))];
+        let root = Rc::new(RefCell::new(self.clone()));
+        let order = topological_order(&root);
+
+        // Intermediate grads left by an earlier pass would otherwise be propagated again on top of this one
+        for node in &order {
+            if !Rc::ptr_eq(node, &root) && !node.borrow().is_leaf() {
+                node.borrow_mut().grad = None;
+            }
+        }
 
-        while let Some(node) = stack.pop() {
-            if let Some(creator_weak) = &node.borrow().creator {
-                if let Some(creator) = creator_weak.upgrade() {
-                    let grad = node.borrow().grad.clone().unwrap();
+        // Outputs come before their inputs, so each node's gradient is complete before it propagates
+        for node in order.into_iter().rev() {
+            let creator = node.borrow().creator.clone();
+            if let Some(creator) = creator {
+                let grad = match node.borrow().grad.clone() {
+                    Some(grad) => grad,
+                    None => continue, // No gradient flowed into this node
+                };
 
-                    {
-                        let backward_fn = &creator.borrow().backward_fn;
-                        backward_fn(&grad, &mut creator.borrow_mut().inputs);
-                    }
+                {
+                    let GraphNode { inputs, backward_fn, .. } = &mut *creator.borrow_mut();
+                    backward_fn(&grad, inputs);
+                }
 
-                    for input in &creator.borrow().inputs {
-                        stack.push(input.clone());
-                    }
+                if leaf_only {
+                    node.borrow_mut().grad = None;
                 }
             }
         }
+
+        if leaf_only && !self.is_leaf() {
+            self.grad = None;
+        }
     }
 }
 
+/// Returns every tensor reachable from `root` in post-order, so each tensor appears after all of its inputs.
+/// Tensors shared by several ops are visited once, keyed by their `Rc` pointer.
+fn topological_order(root: &Rc<RefCell<Tensor>>) -> Vec<Rc<RefCell<Tensor>>> {
+    let mut order = Vec::new();
+    let mut visited = HashSet::new();
+    let mut stack = vec![(root.clone(), false)];
+
+    while let Some((node, expanded)) = stack.pop() {
+        if expanded {
+            order.push(node);
+            continue;
+        }
+        if !visited.insert(Rc::as_ptr(&node)) {
+            continue;
+        }
+
+        stack.push((node.clone(), true));
+        if let Some(creator) = &node.borrow().creator {
+            for input in &creator.borrow().inputs {
+                if !visited.contains(&Rc::as_ptr(input)) {
+                    stack.push((input.clone(), false));
+                }
+            }
+        }
+    }
+
+    order
+}
+
 pub struct GraphNode {
     pub operation: String,
     pub inputs: Vec<Rc<RefCell<Tensor>>>,
-    pub backward_fn: Box<dyn Fn(&Array<f64, ndarray::IxDyn>, &mut Vec<Rc<RefCell<Tensor>>>)>,
+    pub backward_fn: BackwardFn,
 }
 
 impl GraphNode {
     pub fn new(
         operation: String,
         inputs: Vec<Rc<RefCell<Tensor>>>,
-        backward_fn: Box<dyn Fn(&Array<f64, ndarray::IxDyn>, &mut Vec<Rc<RefCell<Tensor>>>)>,
+        backward_fn: BackwardFn,
     ) -> Self {
         GraphNode {
             operation,
@@ -70,9 +196,58 @@ impl GraphNode {
     }
 }
 
+thread_local! {
+    static GRAD_ENABLED: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Whether ops currently record graph nodes; false while a `NoGradGuard` is alive.
+pub fn is_grad_enabled() -> bool {
+    GRAD_ENABLED.with(|enabled| enabled.get())
+}
+
+/// Disables graph construction on this thread until dropped, then restores the previous state,
+/// including when unwinding from a panic. Guards nest.
+pub struct NoGradGuard {
+    previous: bool,
+}
+
+impl NoGradGuard {
+    pub fn new() -> Self {
+        let previous = GRAD_ENABLED.with(|enabled| enabled.replace(false));
+        NoGradGuard { previous }
+    }
+}
+
+impl Default for NoGradGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for NoGradGuard {
+    fn drop(&mut self) {
+        GRAD_ENABLED.with(|enabled| enabled.set(self.previous));
+    }
+}
+
+/// Runs `f` with graph construction disabled, e.g. for inference or validation loops.
+pub fn no_grad<T, F: FnOnce() -> T>(f: F) -> T {
+    let _guard = NoGradGuard::new();
+    f()
+}
+
+/// Sums `grad` over the axes broadcasting expanded, so an operand of `target_shape` receives a gradient of its own shape.
+fn reduce_grad_to_shape(grad: &Array<f64, ndarray::IxDyn>, target_shape: &[usize]) -> Array<f64, ndarray::IxDyn> {
+    if grad.shape() == target_shape {
+        grad.clone()
+    } else {
+        sum_to_shape(grad, target_shape)
+    }
+}
+
 pub fn add(tensor1: &Rc<RefCell<Tensor>>, tensor2: &Rc<RefCell<Tensor>>) -> Rc<RefCell<Tensor>> {
     let data = &tensor1.borrow().data + &tensor2.borrow().data;
-    let requires_grad = tensor1.borrow().requires_grad || tensor2.borrow().requires_grad;
+    let requires_grad = (tensor1.borrow().requires_grad || tensor2.borrow().requires_grad) && is_grad_enabled();
 
     let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
 
@@ -81,12 +256,450 @@ pub fn add(tensor1: &Rc<RefCell<Tensor>>, tensor2: &Rc<RefCell<Tensor>>) -> Rc<R
             "add".to_string(),
             vec![tensor1.clone(), tensor2.clone()],
             Box::new(move |grad, inputs| {
-                inputs[0].borrow_mut().grad = Some(grad.clone());
-                inputs[1].borrow_mut().grad = Some(grad.clone());
+                let grad1 = reduce_grad_to_shape(grad, inputs[0].borrow().data.shape());
+                let grad2 = reduce_grad_to_shape(grad, inputs[1].borrow().data.shape());
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(&grad1);
+                }
+                if inputs[1].borrow().requires_grad {
+                    inputs[1].borrow_mut().accumulate_grad(&grad2);
+                }
+            }),
+        );
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
+    }
+
+    output
+}
+
+pub fn mul(tensor1: &Rc<RefCell<Tensor>>, tensor2: &Rc<RefCell<Tensor>>) -> Rc<RefCell<Tensor>> {
+    let data = &tensor1.borrow().data * &tensor2.borrow().data;
+    let requires_grad = (tensor1.borrow().requires_grad || tensor2.borrow().requires_grad) && is_grad_enabled();
+
+    let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
+
+    if requires_grad {
+        let node = GraphNode::new(
+            "mul".to_string(),
+            vec![tensor1.clone(), tensor2.clone()],
+            Box::new(move |grad, inputs| {
+                // Compute both grads before borrowing mutably, since the inputs may be the same tensor
+                let (grad1, grad2) = {
+                    let a = &inputs[0].borrow().data;
+                    let b = &inputs[1].borrow().data;
+                    (reduce_grad_to_shape(&(grad * b), a.shape()), reduce_grad_to_shape(&(grad * a), b.shape()))
+                };
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(&grad1);
+                }
+                if inputs[1].borrow().requires_grad {
+                    inputs[1].borrow_mut().accumulate_grad(&grad2);
+                }
             }),
         );
-        output.borrow_mut().creator = Some(Rc::downgrade(&Rc::new(RefCell::new(node))));
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
     }
 
     output
 }
+
+pub fn sub(tensor1: &Rc<RefCell<Tensor>>, tensor2: &Rc<RefCell<Tensor>>) -> Rc<RefCell<Tensor>> {
+    let data = &tensor1.borrow().data - &tensor2.borrow().data;
+    let requires_grad = (tensor1.borrow().requires_grad || tensor2.borrow().requires_grad) && is_grad_enabled();
+
+    let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
+
+    if requires_grad {
+        let node = GraphNode::new(
+            "sub".to_string(),
+            vec![tensor1.clone(), tensor2.clone()],
+            Box::new(move |grad, inputs| {
+                let grad1 = reduce_grad_to_shape(grad, inputs[0].borrow().data.shape());
+                let grad2 = reduce_grad_to_shape(&-grad, inputs[1].borrow().data.shape());
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(&grad1);
+                }
+                if inputs[1].borrow().requires_grad {
+                    inputs[1].borrow_mut().accumulate_grad(&grad2);
+                }
+            }),
+        );
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
+    }
+
+    output
+}
+
+/// Divides elementwise. Like ndarray, dividing by zero follows IEEE rules and gives inf or NaN
+/// rather than panicking, in both the forward values and the gradients.
+pub fn div(tensor1: &Rc<RefCell<Tensor>>, tensor2: &Rc<RefCell<Tensor>>) -> Rc<RefCell<Tensor>> {
+    let data = &tensor1.borrow().data / &tensor2.borrow().data;
+    let requires_grad = (tensor1.borrow().requires_grad || tensor2.borrow().requires_grad) && is_grad_enabled();
+
+    let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
+
+    if requires_grad {
+        let node = GraphNode::new(
+            "div".to_string(),
+            vec![tensor1.clone(), tensor2.clone()],
+            Box::new(move |grad, inputs| {
+                let (grad1, grad2) = {
+                    let numerator = &inputs[0].borrow().data;
+                    let denominator = &inputs[1].borrow().data;
+                    (
+                        reduce_grad_to_shape(&(grad / denominator), numerator.shape()),
+                        reduce_grad_to_shape(&(-(grad * numerator) / (denominator * denominator)), denominator.shape()),
+                    )
+                };
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(&grad1);
+                }
+                if inputs[1].borrow().requires_grad {
+                    inputs[1].borrow_mut().accumulate_grad(&grad2);
+                }
+            }),
+        );
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
+    }
+
+    output
+}
+
+/// Multiplies two 2D tensors. The backward pass sends `grad · bᵀ` to `a` and `aᵀ · grad` to `b`.
+///
+/// Panics unless both tensors are 2D with matching inner dimensions; the backward formula doesn't hold
+/// for the other ranks `dot` accepts.
+pub fn matmul(tensor1: &Rc<RefCell<Tensor>>, tensor2: &Rc<RefCell<Tensor>>) -> Rc<RefCell<Tensor>> {
+    let (rank1, rank2) = (tensor1.borrow().data.ndim(), tensor2.borrow().data.ndim());
+    assert!(rank1 == 2 && rank2 == 2, "matmul expects 2D tensors, got ranks {} and {}", rank1, rank2);
+    let data = dot(&tensor1.borrow().data, &tensor2.borrow().data).unwrap_or_else(|e| panic!("matmul: {}", e));
+    let requires_grad = (tensor1.borrow().requires_grad || tensor2.borrow().requires_grad) && is_grad_enabled();
+
+    let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
+
+    if requires_grad {
+        let node = GraphNode::new(
+            "matmul".to_string(),
+            vec![tensor1.clone(), tensor2.clone()],
+            Box::new(move |grad, inputs| {
+                let (grad1, grad2) = {
+                    let a = &inputs[0].borrow().data;
+                    let b = &inputs[1].borrow().data;
+                    (dot_unwrap(grad, &b.t().to_owned()), dot_unwrap(&a.t().to_owned(), grad))
+                };
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(&grad1);
+                }
+                if inputs[1].borrow().requires_grad {
+                    inputs[1].borrow_mut().accumulate_grad(&grad2);
+                }
+            }),
+        );
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
+    }
+
+    output
+}
+
+pub fn relu(tensor: &Rc<RefCell<Tensor>>) -> Rc<RefCell<Tensor>> {
+    let data = tensor.borrow().data.mapv(|x| x.max(0.0));
+    let requires_grad = tensor.borrow().requires_grad && is_grad_enabled();
+
+    let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
+
+    if requires_grad {
+        let node = GraphNode::new(
+            "relu".to_string(),
+            vec![tensor.clone()],
+            Box::new(move |grad, inputs| {
+                // Mask on the saved input: the gradient only passes where x > 0
+                let masked = {
+                    let input = &inputs[0].borrow().data;
+                    ndarray::Zip::from(grad).and(input).map_collect(|&g, &x| if x > 0.0 { g } else { 0.0 })
+                };
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(&masked);
+                }
+            }),
+        );
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
+    }
+
+    output
+}
+
+pub fn sigmoid(tensor: &Rc<RefCell<Tensor>>) -> Rc<RefCell<Tensor>> {
+    let data = tensor.borrow().data.mapv(|x| 1.0 / (1.0 + (-x).exp()));
+    let requires_grad = tensor.borrow().requires_grad && is_grad_enabled();
+
+    // Keep a copy of the output rather than the output tensor itself, which would be a reference cycle
+    let saved = data.clone();
+    let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
+
+    if requires_grad {
+        let node = GraphNode::new(
+            "sigmoid".to_string(),
+            vec![tensor.clone()],
+            Box::new(move |grad, inputs| {
+                let local = saved.mapv(|y| y * (1.0 - y));
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(&(grad * &local));
+                }
+            }),
+        );
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
+    }
+
+    output
+}
+
+pub fn tanh(tensor: &Rc<RefCell<Tensor>>) -> Rc<RefCell<Tensor>> {
+    let data = tensor.borrow().data.mapv(f64::tanh);
+    let requires_grad = tensor.borrow().requires_grad && is_grad_enabled();
+
+    let saved = data.clone();
+    let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
+
+    if requires_grad {
+        let node = GraphNode::new(
+            "tanh".to_string(),
+            vec![tensor.clone()],
+            Box::new(move |grad, inputs| {
+                let local = saved.mapv(|y| 1.0 - y * y);
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(&(grad * &local));
+                }
+            }),
+        );
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
+    }
+
+    output
+}
+
+/// Computes `x - logsumexp(x)` along `axis`, for inputs of any rank.
+pub fn log_softmax(tensor: &Rc<RefCell<Tensor>>, axis: Axis) -> Rc<RefCell<Tensor>> {
+    let data = {
+        let input = &tensor.borrow().data;
+        input - &logsumexp_axis(input, axis).insert_axis(axis)
+    };
+    let requires_grad = tensor.borrow().requires_grad && is_grad_enabled();
+
+    let saved = data.clone();
+    let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
+
+    if requires_grad {
+        let node = GraphNode::new(
+            "log_softmax".to_string(),
+            vec![tensor.clone()],
+            Box::new(move |grad, inputs| {
+                let softmax = saved.mapv(f64::exp);
+                let grad_sum = grad.sum_axis(axis).insert_axis(axis);
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(&(grad - &(softmax * grad_sum)));
+                }
+            }),
+        );
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
+    }
+
+    output
+}
+
+pub fn exp(tensor: &Rc<RefCell<Tensor>>) -> Rc<RefCell<Tensor>> {
+    let data = tensor.borrow().data.mapv(f64::exp);
+    let requires_grad = tensor.borrow().requires_grad && is_grad_enabled();
+
+    let saved = data.clone();
+    let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
+
+    if requires_grad {
+        let node = GraphNode::new(
+            "exp".to_string(),
+            vec![tensor.clone()],
+            Box::new(move |grad, inputs| {
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(&(grad * &saved));
+                }
+            }),
+        );
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
+    }
+
+    output
+}
+
+/// Natural log, defined for positive inputs. Inputs aren't clamped: zero gives `-inf` (with an
+/// infinite gradient) and negatives give NaN, matching `f64::ln`.
+pub fn ln(tensor: &Rc<RefCell<Tensor>>) -> Rc<RefCell<Tensor>> {
+    let data = tensor.borrow().data.mapv(f64::ln);
+    let requires_grad = tensor.borrow().requires_grad && is_grad_enabled();
+
+    let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
+
+    if requires_grad {
+        let node = GraphNode::new(
+            "ln".to_string(),
+            vec![tensor.clone()],
+            Box::new(move |grad, inputs| {
+                let local = grad / &inputs[0].borrow().data;
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(&local);
+                }
+            }),
+        );
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
+    }
+
+    output
+}
+
+/// Raises each element to `exponent`. An exponent of 0 has a zero gradient everywhere (including
+/// x = 0); fractional exponents of negative bases give NaN in both passes, matching `f64::powf`.
+pub fn powf(tensor: &Rc<RefCell<Tensor>>, exponent: f64) -> Rc<RefCell<Tensor>> {
+    let data = tensor.borrow().data.mapv(|x| x.powf(exponent));
+    let requires_grad = tensor.borrow().requires_grad && is_grad_enabled();
+
+    let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
+
+    if requires_grad {
+        let node = GraphNode::new(
+            "powf".to_string(),
+            vec![tensor.clone()],
+            Box::new(move |grad, inputs| {
+                let local = if exponent == 0.0 {
+                    Array::zeros(grad.raw_dim())
+                } else {
+                    grad * &inputs[0].borrow().data.mapv(|x| exponent * x.powf(exponent - 1.0))
+                };
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(&local);
+                }
+            }),
+        );
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
+    }
+
+    output
+}
+
+/// Reduces a tensor of any rank to its sum, with shape `[1]`, so `backward` can start from a scalar loss.
+pub fn sum(tensor: &Rc<RefCell<Tensor>>) -> Rc<RefCell<Tensor>> {
+    let data = Array::from_elem(ndarray::IxDyn(&[1]), tensor.borrow().data.sum());
+    let requires_grad = tensor.borrow().requires_grad && is_grad_enabled();
+
+    let input_dim = tensor.borrow().data.raw_dim();
+    let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
+
+    if requires_grad {
+        let node = GraphNode::new(
+            "sum".to_string(),
+            vec![tensor.clone()],
+            Box::new(move |grad, inputs| {
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(&Array::from_elem(input_dim.clone(), grad[0]));
+                }
+            }),
+        );
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
+    }
+
+    output
+}
+
+/// Reduces a tensor of any rank to its mean, with shape `[1]`; backward spreads `grad / n` over every element.
+pub fn mean(tensor: &Rc<RefCell<Tensor>>) -> Rc<RefCell<Tensor>> {
+    let n = tensor.borrow().data.len() as f64;
+    let data = Array::from_elem(ndarray::IxDyn(&[1]), tensor.borrow().data.sum() / n);
+    let requires_grad = tensor.borrow().requires_grad && is_grad_enabled();
+
+    let input_dim = tensor.borrow().data.raw_dim();
+    let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
+
+    if requires_grad {
+        let node = GraphNode::new(
+            "mean".to_string(),
+            vec![tensor.clone()],
+            Box::new(move |grad, inputs| {
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(&Array::from_elem(input_dim.clone(), grad[0] / n));
+                }
+            }),
+        );
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
+    }
+
+    output
+}
+
+/// Adds a constant to every element; the gradient passes through unchanged.
+pub fn add_scalar(tensor: &Rc<RefCell<Tensor>>, scalar: f64) -> Rc<RefCell<Tensor>> {
+    let data = &tensor.borrow().data + scalar;
+    let requires_grad = tensor.borrow().requires_grad && is_grad_enabled();
+
+    let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
+
+    if requires_grad {
+        let node = GraphNode::new(
+            "add_scalar".to_string(),
+            vec![tensor.clone()],
+            Box::new(move |grad, inputs| {
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(grad);
+                }
+            }),
+        );
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
+    }
+
+    output
+}
+
+/// Scales every element by a constant; the gradient is scaled by the same constant.
+pub fn mul_scalar(tensor: &Rc<RefCell<Tensor>>, scalar: f64) -> Rc<RefCell<Tensor>> {
+    let data = &tensor.borrow().data * scalar;
+    let requires_grad = tensor.borrow().requires_grad && is_grad_enabled();
+
+    let output = Rc::new(RefCell::new(Tensor::new(data, requires_grad)));
+
+    if requires_grad {
+        let node = GraphNode::new(
+            "mul_scalar".to_string(),
+            vec![tensor.clone()],
+            Box::new(move |grad, inputs| {
+                if inputs[0].borrow().requires_grad {
+                    inputs[0].borrow_mut().accumulate_grad(&(grad * scalar));
+                }
+            }),
+        );
+        output.borrow_mut().creator = Some(Rc::new(RefCell::new(node)));
+    }
+
+    output
+}
+
+/// Builds the `[outputs, inputs]` Jacobian of `f` at `input`, flattening both sides, by running one backward
+/// pass per output element on a freshly recorded graph. `input` should be a leaf; its grad is restored afterwards,
+/// and intermediate tensors, including ones built outside `f`, are left without a grad.
+pub fn jacobian(f: impl Fn(&Rc<RefCell<Tensor>>) -> Rc<RefCell<Tensor>>, input: &Rc<RefCell<Tensor>>) -> Array<f64, ndarray::IxDyn> {
+    let saved_grad = input.borrow_mut().grad.take();
+    let input_len = input.borrow().data.len();
+    let output_len = f(input).borrow().data.len();
+
+    let mut result = ndarray::Array2::zeros((output_len, input_len));
+    for (i, mut row) in result.outer_iter_mut().enumerate() {
+        input.borrow_mut().zero_grad();
+        let output = f(input);
+        let mut seed = Array::zeros(output.borrow().data.raw_dim());
+        seed.as_slice_mut().unwrap()[i] = 1.0;
+        output.borrow_mut().grad = Some(seed);
+        output.borrow_mut().backward_leaf_only(); // Keeps intermediates outside `f` free of stray grads
+
+        if let Some(grad) = &input.borrow().grad {
+            row.assign(&Array::from_iter(grad.iter().cloned()));
+        }
+    }
+
+    input.borrow_mut().grad = saved_grad;
+    result.into_dyn()
+}
@@ -0,0 +1,250 @@
+use crate::tensor::covariance;
+use ndarray::{Array, Array2, Axis, Ix2, IxDyn};
+use ndarray_linalg::{Eigh, UPLO};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Parses a numeric CSV file into a 2D `[rows, columns]` array, optionally skipping a header row.
+pub fn from_csv(path: &Path, has_header: bool) -> io::Result<Array<f64, IxDyn>> {
+    let contents = fs::read_to_string(path)?;
+    let mut lines = contents.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    if has_header {
+        lines.next();
+    }
+
+    let mut data = Vec::new();
+    let mut columns = None;
+    let mut rows = 0;
+    for (line_no, line) in lines {
+        let mut count = 0;
+        for cell in line.split(',') {
+            let value = cell.trim().parse::<f64>().map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("line {}: non-numeric cell {:?}", line_no + 1, cell.trim()))
+            })?;
+            data.push(value);
+            count += 1;
+        }
+        match columns {
+            None => columns = Some(count),
+            Some(expected) if expected != count => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: expected {} columns, got {}", line_no + 1, expected, count),
+                ));
+            }
+            _ => {}
+        }
+        rows += 1;
+    }
+
+    Array::from_shape_vec(vec![rows, columns.unwrap_or(0)], data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Shuffles `values` in place with the Fisher-Yates algorithm.
+fn fisher_yates(values: &mut [usize], rng: &mut StdRng) {
+    for i in (1..values.len()).rev() {
+        let j = rng.gen_range(0..=i);
+        values.swap(i, j);
+    }
+}
+
+/// Returns a seeded Fisher-Yates permutation of `0..n`.
+pub fn shuffle_indices(n: usize, seed: u64) -> Vec<usize> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut indices: Vec<usize> = (0..n).collect();
+    fisher_yates(&mut indices, &mut rng);
+    indices
+}
+
+/// Splits `0..n` into `k` shuffled folds, returning `(train, val)` indices for each fold.
+/// Every sample appears in exactly one validation set; fold sizes differ by at most one.
+pub fn kfold_indices(n: usize, k: usize, seed: u64) -> Vec<(Vec<usize>, Vec<usize>)> {
+    assert!(k > 0 && k <= n, "k ({}) must be between 1 and the number of samples ({})", k, n);
+    let indices = shuffle_indices(n, seed);
+    let (base, extra) = (n / k, n % k);
+
+    let mut folds = Vec::with_capacity(k);
+    let mut start = 0;
+    for fold in 0..k {
+        let end = start + base + usize::from(fold < extra);
+        let val = indices[start..end].to_vec();
+        let train = indices[..start].iter().chain(indices[end..].iter()).cloned().collect();
+        folds.push((train, val));
+        start = end;
+    }
+    folds
+}
+
+/// Splits sample indices into `(train, test)` so each class keeps roughly the same proportion in both.
+pub fn stratified_split(labels: &Array<usize, IxDyn>, test_fraction: f64, seed: u64) -> (Vec<usize>, Vec<usize>) {
+    assert!((0.0..=1.0).contains(&test_fraction), "test_fraction must be in [0, 1], got {}", test_fraction);
+    let mut by_class: BTreeMap<usize, Vec<usize>> = BTreeMap::new(); // Ordered so the split is reproducible
+    for (i, &label) in labels.iter().enumerate() {
+        by_class.entry(label).or_default().push(i);
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (mut train, mut test) = (Vec::new(), Vec::new());
+    for members in by_class.values_mut() {
+        fisher_yates(members, &mut rng);
+        let n_test = (members.len() as f64 * test_fraction).round() as usize;
+        test.extend_from_slice(&members[..n_test]);
+        train.extend_from_slice(&members[n_test..]);
+    }
+    train.sort_unstable();
+    test.sort_unstable();
+    (train, test)
+}
+
+/// Expands an `[N, F]` input into all monomials of its features up to `degree`, ordered by degree
+/// then lexicographically (`1, x0, x1, x0^2, x0*x1, x1^2, ...`).
+pub fn polynomial_features(input: &Array<f64, IxDyn>, degree: usize, include_bias: bool) -> Array<f64, IxDyn> {
+    let samples = input.view().into_dimensionality::<ndarray::Ix2>().unwrap();
+    let n_features = samples.ncols();
+
+    // Each term is a non-decreasing list of feature indices, i.e. a combination with replacement
+    let mut terms: Vec<Vec<usize>> = if include_bias { vec![vec![]] } else { vec![] };
+    let mut previous: Vec<Vec<usize>> = vec![vec![]];
+    for _ in 0..degree {
+        let mut current = Vec::new();
+        for term in &previous {
+            let start = term.last().copied().unwrap_or(0);
+            for feature in start..n_features {
+                let mut next = term.clone();
+                next.push(feature);
+                current.push(next);
+            }
+        }
+        terms.extend(current.iter().cloned());
+        previous = current;
+    }
+
+    let mut output = ndarray::Array2::zeros((samples.nrows(), terms.len()));
+    for (mut out_row, row) in output.outer_iter_mut().zip(samples.outer_iter()) {
+        for (value, term) in out_row.iter_mut().zip(terms.iter()) {
+            *value = term.iter().map(|&feature| row[feature]).product();
+        }
+    }
+    output.into_dyn()
+}
+
+/// Clusters the rows of a `[N, F]` data matrix with Lloyd's algorithm, starting from `k` distinct rows
+/// chosen by `seed`. Returns the `[k, F]` centroids and the `[N]` cluster assignment of each row.
+///
+/// `max_iters` bounds the number of centroid updates. The returned assignments always come from a pass
+/// against the returned centroids, so `max_iters == 0` assigns every row to its nearest initial centroid.
+pub fn kmeans(data: &Array<f64, IxDyn>, k: usize, max_iters: usize, seed: u64) -> (Array<f64, IxDyn>, Array<usize, IxDyn>) {
+    let samples = data.view().into_dimensionality::<Ix2>().unwrap();
+    let n = samples.nrows();
+    assert!(k > 0 && k <= n, "k ({}) must be between 1 and the number of samples ({})", k, n);
+
+    let mut centroids = samples.select(Axis(0), &shuffle_indices(n, seed)[..k]);
+    let mut assignments = vec![usize::MAX; n];
+    let mut iteration = 0;
+    loop {
+        let mut changed = false;
+        for (i, sample) in samples.outer_iter().enumerate() {
+            let nearest = centroids
+                .outer_iter()
+                .map(|centroid| (&centroid - &sample).mapv(|x| x * x).sum())
+                .enumerate()
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                .unwrap()
+                .0;
+            if assignments[i] != nearest {
+                assignments[i] = nearest;
+                changed = true;
+            }
+        }
+        if !changed || iteration == max_iters {
+            break;
+        }
+        iteration += 1;
+
+        for (cluster, mut centroid) in centroids.outer_iter_mut().enumerate() {
+            let members: Vec<usize> = (0..n).filter(|&i| assignments[i] == cluster).collect();
+            if !members.is_empty() {
+                centroid.assign(&samples.select(Axis(0), &members).mean_axis(Axis(0)).unwrap()); // Empty clusters keep their centroid
+            }
+        }
+    }
+
+    (centroids.into_dyn(), Array::from_vec(assignments).into_dyn())
+}
+
+/// Mixup augmentation: blends two samples (or batches) as `lam * x1 + (1 - lam) * x2`.
+pub fn mixup(x1: &Array<f64, IxDyn>, x2: &Array<f64, IxDyn>, lam: f64) -> Array<f64, IxDyn> {
+    assert_eq!(x1.shape(), x2.shape(), "mixup inputs must have the same shape");
+    assert!((0.0..=1.0).contains(&lam), "lam must be in [0, 1], got {}", lam);
+    x1 * lam + x2 * (1.0 - lam)
+}
+
+/// Builds the matching mixup targets for `[batch]` class indices: one-hot `[batch, num_classes]` rows
+/// holding `lam` on each `labels1` class and `1 - lam` on each `labels2` class.
+pub fn mixup_labels(labels1: &Array<usize, IxDyn>, labels2: &Array<usize, IxDyn>, num_classes: usize, lam: f64) -> Array<f64, IxDyn> {
+    assert_eq!(labels1.len(), labels2.len(), "mixup label batches must have the same length");
+    let one_hot = |labels: &Array<usize, IxDyn>| {
+        let mut encoded = Array2::<f64>::zeros((labels.len(), num_classes));
+        for (row, &label) in labels.iter().enumerate() {
+            encoded[[row, label]] = 1.0;
+        }
+        encoded.into_dyn()
+    };
+    mixup(&one_hot(labels1), &one_hot(labels2), lam)
+}
+
+/// Principal component analysis fitted on the eigendecomposition of the feature covariance.
+pub struct PCA {
+    pub components: Array<f64, IxDyn>, // [n_components, F], one principal axis per row
+    pub mean: Array<f64, IxDyn>,       // [F]
+}
+
+impl PCA {
+    pub fn new() -> Self {
+        PCA {
+            components: Array::zeros(IxDyn(&[0, 0])),
+            mean: Array::zeros(IxDyn(&[0])),
+        }
+    }
+
+    /// Fits the top `n_components` principal axes of a `[N, F]` data matrix, ordered by explained variance.
+    pub fn fit(&mut self, data: &Array<f64, IxDyn>, n_components: usize) {
+        let features = data.shape()[1];
+        assert!(n_components <= features, "n_components ({}) must not exceed the number of features ({})", n_components, features);
+
+        let cov = covariance(data, 1.0).into_dimensionality::<Ix2>().unwrap();
+        let (_, eigenvectors) = cov.eigh(UPLO::Lower).unwrap();
+
+        // eigh returns eigenvalues in ascending order, so the largest are the last columns
+        let mut components = Array2::zeros((n_components, features));
+        for (i, mut row) in components.outer_iter_mut().enumerate() {
+            row.assign(&eigenvectors.column(features - 1 - i));
+        }
+        self.components = components.into_dyn();
+        self.mean = data.mean_axis(Axis(0)).unwrap();
+    }
+
+    /// Projects a `[N, F]` data matrix onto the fitted components, giving `[N, n_components]`.
+    pub fn transform(&self, data: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
+        let centered = (data - &self.mean).into_dimensionality::<Ix2>().unwrap();
+        let components = self.components.view().into_dimensionality::<Ix2>().unwrap();
+        centered.dot(&components.t()).into_dyn()
+    }
+
+    /// Maps `[N, n_components]` projections back into the original `[N, F]` feature space.
+    pub fn inverse_transform(&self, projected: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
+        let projected = projected.view().into_dimensionality::<Ix2>().unwrap();
+        let components = self.components.view().into_dimensionality::<Ix2>().unwrap();
+        (projected.dot(&components) + &self.mean).into_dyn()
+    }
+}
+
+impl Default for PCA {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,4 +1,8 @@
 #![recursion_limit = "1024"]
 pub mod tensor;
 pub mod autograd;
-pub mod optimizer;
\ No newline at end of file
+pub mod optimizer;
+pub mod scheduler;
+pub mod data;
+pub mod nn;
+pub mod metrics;
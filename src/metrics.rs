@@ -0,0 +1,98 @@
+use ndarray::{Array, Axis, IxDyn};
+
+/// Accumulates a running, sample-weighted average of per-batch values.
+#[derive(Default)]
+pub struct AverageMeter {
+    sum: f64,
+    count: usize,
+}
+
+impl AverageMeter {
+    pub fn new() -> Self {
+        AverageMeter { sum: 0.0, count: 0 }
+    }
+
+    /// Records a batch whose mean value is `value` over `n` samples.
+    pub fn update(&mut self, value: f64, n: usize) {
+        self.sum += value * n as f64;
+        self.count += n;
+    }
+
+    /// Weighted mean of everything recorded so far, or 0 if nothing has been recorded.
+    pub fn average(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum / self.count as f64
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.sum = 0.0;
+        self.count = 0;
+    }
+}
+
+/// Fraction of `[batch, classes]` rows whose target class is among the `k` highest logits.
+/// Ties with the target's logit are resolved in the target's favor.
+pub fn topk_accuracy(logits: &Array<f64, IxDyn>, targets: &Array<usize, IxDyn>, k: usize) -> f64 {
+    let logits_2d = logits.view().into_dimensionality::<ndarray::Ix2>().unwrap();
+    let correct = logits_2d
+        .axis_iter(Axis(0))
+        .zip(targets.iter())
+        .filter(|(row, &target)| row.iter().filter(|&&x| x > row[target]).count() < k)
+        .count();
+    correct as f64 / logits_2d.nrows() as f64
+}
+
+/// Area under the ROC curve for binary `labels` (0 or 1), from the Mann-Whitney U statistic.
+/// Tied scores receive their average rank.
+pub fn roc_auc(scores: &Array<f64, IxDyn>, labels: &Array<usize, IxDyn>) -> f64 {
+    if let Some(&bad) = labels.iter().find(|&&label| label > 1) {
+        panic!("roc_auc labels must be 0 or 1, got {}", bad);
+    }
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    let scores: Vec<f64> = scores.iter().cloned().collect();
+    let labels: Vec<usize> = labels.iter().cloned().collect();
+    order.sort_by(|&a, &b| scores[a].partial_cmp(&scores[b]).unwrap());
+
+    let mut ranks = vec![0.0; scores.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && scores[order[end]] == scores[order[start]] {
+            end += 1;
+        }
+        let average_rank = (start + end + 1) as f64 / 2.0; // Mean of the 1-based ranks start+1..=end
+        for &i in &order[start..end] {
+            ranks[i] = average_rank;
+        }
+        start = end;
+    }
+
+    let n_pos = labels.iter().filter(|&&label| label == 1).count() as f64;
+    let n_neg = labels.len() as f64 - n_pos;
+    assert!(n_pos > 0.0 && n_neg > 0.0, "roc_auc needs both positive and negative labels");
+    let positive_rank_sum: f64 = ranks.iter().zip(labels.iter()).filter(|(_, &label)| label == 1).map(|(rank, _)| rank).sum();
+    (positive_rank_sum - n_pos * (n_pos + 1.0) / 2.0) / (n_pos * n_neg)
+}
+
+/// Mean absolute error between predictions and targets.
+pub fn mae(pred: &Array<f64, IxDyn>, target: &Array<f64, IxDyn>) -> f64 {
+    (pred - target).mapv(f64::abs).mean().unwrap_or(0.0)
+}
+
+/// Mean absolute percentage error, in percent. Entries with a zero target are skipped
+/// since their relative error is undefined; returns 0 if every target is zero.
+pub fn mape(pred: &Array<f64, IxDyn>, target: &Array<f64, IxDyn>) -> f64 {
+    let (total, count) = pred
+        .iter()
+        .zip(target.iter())
+        .filter(|(_, &t)| t != 0.0)
+        .fold((0.0, 0usize), |(total, count), (&p, &t)| (total + ((p - t) / t).abs(), count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        100.0 * total / count as f64
+    }
+}
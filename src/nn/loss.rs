@@ -0,0 +1,55 @@
+use crate::autograd::{log_softmax, mean, mul, mul_scalar, sub, sum, Tensor};
+use ndarray::{Array, Axis, IxDyn};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Smallest probability fed to `ln`, so a zero probability gives a large finite loss instead of infinity.
+const PROB_EPSILON: f64 = 1e-12;
+
+/// Computes the mean cross-entropy of `[batch, classes]` probabilities against `[batch]` class indices.
+pub fn cross_entropy_from_probs(probs: &Array<f64, IxDyn>, targets: &Array<usize, IxDyn>) -> f64 {
+    let probs_2d = probs.view().into_dimensionality::<ndarray::Ix2>().unwrap();
+    let total: f64 = probs_2d
+        .axis_iter(Axis(0))
+        .zip(targets.iter())
+        .map(|(row, &target)| -row[target].clamp(PROB_EPSILON, 1.0).ln())
+        .sum();
+    total / probs_2d.nrows() as f64
+}
+
+/// Computes the mean negative log-likelihood of `[batch, classes]` log-probabilities against `[batch]` class indices.
+pub fn nll_loss(log_probs: &Array<f64, IxDyn>, targets: &Array<usize, IxDyn>) -> f64 {
+    let log_probs_2d = log_probs.view().into_dimensionality::<ndarray::Ix2>().unwrap();
+    let total: f64 = log_probs_2d
+        .axis_iter(Axis(0))
+        .zip(targets.iter())
+        .map(|(row, &target)| -row[target])
+        .sum();
+    total / log_probs_2d.nrows() as f64
+}
+
+/// Mean squared error as a differentiable `[1]` tensor built from autograd ops. The target is
+/// detached, so `backward` only fills `pred.grad`, with `2 * (pred - target) / n`.
+pub fn mse_loss(pred: &Rc<RefCell<Tensor>>, target: &Rc<RefCell<Tensor>>) -> Rc<RefCell<Tensor>> {
+    let target = Rc::new(RefCell::new(target.borrow().detach()));
+    let diff = sub(pred, &target);
+    mean(&mul(&diff, &diff))
+}
+
+/// Mean cross-entropy of `[batch, classes]` logits against `[batch]` class indices as a differentiable `[1]`
+/// tensor. The log-probabilities of the targets are gathered by a constant one-hot mask, so the
+/// logits receive `(softmax - one_hot) / batch`.
+pub fn cross_entropy(logits: &Rc<RefCell<Tensor>>, targets: &Array<usize, IxDyn>) -> Rc<RefCell<Tensor>> {
+    let shape = logits.borrow().data.shape().to_vec();
+    assert_eq!(shape.len(), 2, "logits must be [batch, classes], got {:?}", shape);
+    assert_eq!(targets.len(), shape[0], "expected {} targets, got {}", shape[0], targets.len());
+
+    let mut one_hot = Array::zeros(IxDyn(&shape));
+    for (row, &target) in targets.iter().enumerate() {
+        one_hot[[row, target]] = 1.0;
+    }
+    let one_hot = Rc::new(RefCell::new(Tensor::new(one_hot, false)));
+
+    let log_probs = log_softmax(logits, Axis(1));
+    mul_scalar(&sum(&mul(&log_probs, &one_hot)), -1.0 / shape[0] as f64)
+}
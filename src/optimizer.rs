@@ -1,12 +1,66 @@
-use rand::Rng;
+use ndarray::{Array1, Array2, Axis};
+use ndarray_linalg::{Eigh, UPLO};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Evaluates `(loss, grads)` at the given params.
+pub type LossClosure<'a> = dyn FnMut(&[f64]) -> (f64, Vec<f64>) + 'a;
 
 pub trait Optimizer {
     fn step(&mut self, params: &mut Vec<f64>, grads: &[f64]);
     fn reset(&mut self);
+
+    /// Steps using a closure that evaluates `(loss, grads)` at the given params, for optimizers that
+    /// need several evaluations per step. The default evaluates once, delegates to `step`, and returns the loss.
+    fn step_closure(&mut self, params: &mut Vec<f64>, closure: &mut LossClosure<'_>) -> f64 {
+        let (loss, grads) = closure(params);
+        self.step(params, &grads);
+        loss
+    }
+}
+
+/// Tracks the cosine similarity between successive update vectors; negative values indicate oscillation.
+#[derive(Default)]
+struct UpdateTracker {
+    enabled: bool,
+    previous: Vec<f64>,
+    cosine: Option<f64>,
+}
+
+impl UpdateTracker {
+    fn record(&mut self, update: Vec<f64>) {
+        if !self.previous.is_empty() {
+            let dot: f64 = update.iter().zip(self.previous.iter()).map(|(a, b)| a * b).sum();
+            let norms = update.iter().map(|a| a * a).sum::<f64>().sqrt() * self.previous.iter().map(|b| b * b).sum::<f64>().sqrt();
+            self.cosine = if norms > 0.0 { Some(dot / norms) } else { None };
+        }
+        self.previous = update;
+    }
+
+    fn clear(&mut self) {
+        self.previous.clear();
+        self.cosine = None;
+    }
 }
 
 pub struct SGD {
     learning_rate: f64,
+    grad_clip: Option<f64>,
+    tracker: UpdateTracker,
+}
+
+/// Zeroes the state entries at `indices`. Empty state (before the first step) has nothing to reset;
+/// otherwise an index past the state's length panics, since it points at a param that doesn't exist.
+fn zero_indices(state: &mut [f64], indices: &[usize]) {
+    if state.is_empty() {
+        return;
+    }
+    if let Some(&bad) = indices.iter().find(|&&i| i >= state.len()) {
+        panic!("reset index {} is out of range for {} params", bad, state.len());
+    }
+    for &i in indices {
+        state[i] = 0.0;
+    }
 }
 
 pub struct Adam {
@@ -17,6 +71,7 @@ pub struct Adam {
     m: Vec<f64>,
     v: Vec<f64>,
     t: usize,
+    grad_clip: Option<f64>,
 }
 
 pub struct RMSprop {
@@ -36,11 +91,32 @@ pub struct Momentum {
     learning_rate: f64,
     momentum: f64,
     velocity: Vec<f64>,
+    tracker: UpdateTracker,
 }
 
 impl SGD {
     pub fn new(learning_rate: f64) -> Self {
-        SGD { learning_rate }
+        SGD { learning_rate, grad_clip: None, tracker: UpdateTracker::default() }
+    }
+
+    /// Enables tracking of the cosine between consecutive updates, reported by `last_update_cosine`.
+    pub fn set_track_update_cosine(&mut self, enabled: bool) {
+        self.tracker.enabled = enabled;
+    }
+
+    /// Cosine similarity between the last two updates, once tracking has seen two non-zero steps.
+    pub fn last_update_cosine(&self) -> Option<f64> {
+        self.tracker.cosine
+    }
+
+    /// Clamps each gradient to `[-grad_clip, grad_clip]` before the update; `None` disables clipping.
+    ///
+    /// Panics if the limit is negative or NaN.
+    pub fn set_grad_clip(&mut self, grad_clip: Option<f64>) {
+        if let Some(limit) = grad_clip {
+            assert!(limit >= 0.0, "grad_clip must be non-negative, got {}", limit);
+        }
+        self.grad_clip = grad_clip;
     }
 }
 
@@ -54,7 +130,56 @@ impl Adam {
             m: Vec::new(),
             v: Vec::new(),
             t: 0,
+            grad_clip: None,
+        }
+    }
+
+    /// Clamps each gradient to `[-grad_clip, grad_clip]` before the update; `None` disables clipping.
+    ///
+    /// Panics if the limit is negative or NaN.
+    pub fn set_grad_clip(&mut self, grad_clip: Option<f64>) {
+        if let Some(limit) = grad_clip {
+            assert!(limit >= 0.0, "grad_clip must be non-negative, got {}", limit);
+        }
+        self.grad_clip = grad_clip;
+    }
+
+    /// Number of steps taken since construction or the last full `reset`.
+    pub fn current_step(&self) -> usize {
+        self.t
+    }
+
+    /// Zeroes the moment estimates for a warm restart while keeping the step count,
+    /// unlike `reset`, which also restarts bias correction.
+    pub fn soft_reset(&mut self) {
+        self.m.iter_mut().for_each(|m| *m = 0.0);
+        self.v.iter_mut().for_each(|v| *v = 0.0);
+    }
+
+    /// Zeroes the moment estimates of the params at `indices`, e.g. after reinitializing part of a model.
+    pub fn reset_indices(&mut self, indices: &[usize]) {
+        zero_indices(&mut self.m, indices);
+        zero_indices(&mut self.v, indices);
+    }
+
+    /// Returns the bias-corrected first and second moments `(m_hat, v_hat)` as of the last step.
+    /// Both are empty before the first step.
+    pub fn moment_snapshot(&self) -> (Vec<f64>, Vec<f64>) {
+        if self.t == 0 {
+            return (Vec::new(), Vec::new());
         }
+        let m_correction = 1.0 - self.beta1.powi(self.t as i32);
+        let v_correction = 1.0 - self.beta2.powi(self.t as i32);
+        (
+            self.m.iter().map(|m| m / m_correction).collect(),
+            self.v.iter().map(|v| v / v_correction).collect(),
+        )
+    }
+
+    /// Per-parameter step scale `learning_rate / (sqrt(v_hat) + epsilon)` as of the last step; empty before the first.
+    pub fn effective_lr(&self) -> Vec<f64> {
+        let (_, v_hat) = self.moment_snapshot();
+        v_hat.iter().map(|v| self.learning_rate / (v.sqrt() + self.epsilon)).collect()
     }
 }
 
@@ -67,6 +192,11 @@ impl RMSprop {
             cache: Vec::new(),
         }
     }
+
+    /// Zeroes the squared-gradient cache of the params at `indices`.
+    pub fn reset_indices(&mut self, indices: &[usize]) {
+        zero_indices(&mut self.cache, indices);
+    }
 }
 
 impl AdaGrad {
@@ -77,6 +207,11 @@ impl AdaGrad {
             cache: Vec::new(),
         }
     }
+
+    /// Zeroes the accumulated squared gradients of the params at `indices`.
+    pub fn reset_indices(&mut self, indices: &[usize]) {
+        zero_indices(&mut self.cache, indices);
+    }
 }
 
 impl Momentum {
@@ -85,18 +220,49 @@ impl Momentum {
             learning_rate,
             momentum,
             velocity: Vec::new(),
+            tracker: UpdateTracker::default(),
         }
     }
+
+    /// Enables tracking of the cosine between consecutive updates, reported by `last_update_cosine`.
+    pub fn set_track_update_cosine(&mut self, enabled: bool) {
+        self.tracker.enabled = enabled;
+    }
+
+    /// Cosine similarity between the last two updates, once tracking has seen two non-zero steps.
+    pub fn last_update_cosine(&self) -> Option<f64> {
+        self.tracker.cosine
+    }
+
+    /// Zeroes the velocity of the params at `indices`.
+    pub fn reset_indices(&mut self, indices: &[usize]) {
+        zero_indices(&mut self.velocity, indices);
+    }
+}
+
+fn clip_grad(grad: f64, grad_clip: Option<f64>) -> f64 {
+    match grad_clip {
+        Some(limit) => grad.clamp(-limit, limit),
+        None => grad,
+    }
 }
 
 impl Optimizer for SGD {
     fn step(&mut self, params: &mut Vec<f64>, grads: &[f64]) {
         for (param, grad) in params.iter_mut().zip(grads.iter()) {
+            let grad = clip_grad(*grad, self.grad_clip);
             *param -= self.learning_rate * grad;
         }
+
+        if self.tracker.enabled {
+            let update = grads.iter().map(|grad| -self.learning_rate * clip_grad(*grad, self.grad_clip)).collect();
+            self.tracker.record(update);
+        }
     }
 
-    fn reset(&mut self) {}
+    fn reset(&mut self) {
+        self.tracker.clear();
+    }
 }
 
 impl Optimizer for Adam {
@@ -110,6 +276,7 @@ impl Optimizer for Adam {
 
         for ((param, grad), (m, v)) in params.iter_mut().zip(grads.iter())
             .zip(self.m.iter_mut().zip(self.v.iter_mut())) {
+            let grad = clip_grad(*grad, self.grad_clip);
             *m = self.beta1 * *m + (1.0 - self.beta1) * grad;
             *v = self.beta2 * *v + (1.0 - self.beta2) * grad * grad;
 
@@ -174,6 +341,54 @@ impl Optimizer for Momentum {
             *velocity = self.momentum * *velocity - self.learning_rate * grad;
             *param += *velocity;
         }
+
+        if self.tracker.enabled {
+            self.tracker.record(self.velocity.clone());
+        }
+    }
+
+    fn reset(&mut self) {
+        self.velocity.clear();
+        self.tracker.clear();
+    }
+}
+
+/// Layer-wise adaptive rate scaling (You et al., 2017). Each call to `step` treats `params` as one layer
+/// and scales its update by the trust ratio `trust_coefficient * ||w|| / ||g||`.
+pub struct LARS {
+    learning_rate: f64,
+    momentum: f64,
+    trust_coefficient: f64,
+    velocity: Vec<f64>,
+}
+
+impl LARS {
+    pub fn new(learning_rate: f64, momentum: f64, trust_coefficient: f64) -> Self {
+        LARS {
+            learning_rate,
+            momentum,
+            trust_coefficient,
+            velocity: Vec::new(),
+        }
+    }
+}
+
+impl Optimizer for LARS {
+    fn step(&mut self, params: &mut Vec<f64>, grads: &[f64]) {
+        if self.velocity.is_empty() {
+            self.velocity = vec![0.0; params.len()];
+        }
+
+        let param_norm = params.iter().map(|p| p * p).sum::<f64>().sqrt();
+        let grad_norm = grads.iter().map(|g| g * g).sum::<f64>().sqrt();
+        // Fall back to the plain rate when either norm is zero, e.g. zero-initialized layers
+        let trust_ratio = if param_norm > 0.0 && grad_norm > 0.0 { self.trust_coefficient * param_norm / grad_norm } else { 1.0 };
+        let local_lr = self.learning_rate * trust_ratio;
+
+        for ((param, grad), velocity) in params.iter_mut().zip(grads.iter()).zip(self.velocity.iter_mut()) {
+            *velocity = self.momentum * *velocity + local_lr * grad;
+            *param -= *velocity;
+        }
     }
 
     fn reset(&mut self) {
@@ -181,24 +396,72 @@ impl Optimizer for Momentum {
     }
 }
 
+/// Probability of a random jump after `steps` steps when exploration decays at `rate` per step.
+fn exploration_epsilon(rate: f64, steps: usize) -> f64 {
+    (-rate * steps as f64).exp()
+}
+
+/// Moves one param by `step_size` along `direction`, cycling through the dims and flipping
+/// the direction after each full sweep. Needs no gradients.
+fn coordinate_step(params: &mut [f64], step_size: f64, current_dim: &mut usize, direction: &mut i32) {
+    params[*current_dim] += step_size * *direction as f64;
+    *current_dim = (*current_dim + 1) % params.len();
+    if *current_dim == 0 {
+        *direction *= -1;
+    }
+}
+
 pub struct SimpleRandomSearch {
     step_size: f64,
-    rng: rand::rngs::ThreadRng,
+    current_dim: usize,
+    direction: i32,
+    rng: StdRng,
+    seed: Option<u64>,
+    epsilon_schedule: f64, // Decay rate of the random-jump probability; 0 keeps every step random
+    steps: usize,
 }
 
 pub struct GridSearch {
     step_size: f64,
     current_dim: usize,
     direction: i32,
+    rng: StdRng,
+    seed: Option<u64>,
+    epsilon_schedule: f64, // Decay rate of the random-jump probability; 0 disables random jumps
+    steps: usize,
 }
 
 impl SimpleRandomSearch {
     pub fn new(step_size: f64) -> Self {
         SimpleRandomSearch {
             step_size,
-            rng: rand::thread_rng(),
+            current_dim: 0,
+            direction: 1,
+            rng: StdRng::from_entropy(),
+            seed: None,
+            epsilon_schedule: 0.0,
+            steps: 0,
         }
     }
+
+    /// Seeds the RNG so runs are reproducible; `reset` reseeds with the same value.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Makes exploration epsilon-greedy: each step jumps randomly with probability `exp(-rate * t)`
+    /// and otherwise takes a `GridSearch`-style coordinate step. Panics if `rate` is negative or NaN.
+    pub fn set_epsilon_decay(&mut self, rate: f64) {
+        assert!(rate >= 0.0, "epsilon decay rate must be non-negative, got {}", rate);
+        self.epsilon_schedule = rate;
+    }
+
+    /// Current probability of taking a random jump. A rate of 0 keeps it at 1, so every step is random;
+    /// unlike `GridSearch`, whose default is the deterministic sweep.
+    pub fn epsilon(&self) -> f64 {
+        exploration_epsilon(self.epsilon_schedule, self.steps)
+    }
 }
 
 impl GridSearch {
@@ -207,34 +470,364 @@ impl GridSearch {
             step_size,
             current_dim: 0,
             direction: 1,
+            rng: StdRng::from_entropy(),
+            seed: None,
+            epsilon_schedule: 0.0,
+            steps: 0,
+        }
+    }
+
+    /// Seeds the RNG so runs are reproducible; `reset` reseeds with the same value.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Adds epsilon-greedy exploration: each step jumps randomly with probability `exp(-rate * t)`
+    /// and otherwise takes the usual coordinate step. Panics if `rate` is negative or NaN.
+    pub fn set_epsilon_decay(&mut self, rate: f64) {
+        assert!(rate >= 0.0, "epsilon decay rate must be non-negative, got {}", rate);
+        self.epsilon_schedule = rate;
+    }
+
+    /// Current probability of taking a random jump. A rate of 0 disables jumps entirely, keeping the
+    /// plain coordinate sweep, rather than making every step random as in `SimpleRandomSearch`.
+    pub fn epsilon(&self) -> f64 {
+        if self.epsilon_schedule > 0.0 {
+            exploration_epsilon(self.epsilon_schedule, self.steps)
+        } else {
+            0.0
         }
     }
 }
 
 impl Optimizer for SimpleRandomSearch {
     fn step(&mut self, params: &mut Vec<f64>, _grads: &[f64]) {
-        for param in params.iter_mut() {
-            let perturbation = self.rng.gen_range(-self.step_size..self.step_size);
-            *param += perturbation;
+        let epsilon = self.epsilon();
+        self.steps += 1;
+        if self.rng.gen::<f64>() < epsilon {
+            for param in params.iter_mut() {
+                let perturbation = self.rng.gen_range(-self.step_size..self.step_size);
+                *param += perturbation;
+            }
+        } else {
+            coordinate_step(params, self.step_size, &mut self.current_dim, &mut self.direction);
         }
     }
 
     fn reset(&mut self) {
-        self.rng = rand::thread_rng();
+        self.current_dim = 0;
+        self.direction = 1;
+        self.rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        self.steps = 0;
     }
 }
 
 impl Optimizer for GridSearch {
     fn step(&mut self, params: &mut Vec<f64>, _grads: &[f64]) {
-        params[self.current_dim] += self.step_size * self.direction as f64;
-        self.current_dim = (self.current_dim + 1) % params.len();
-        if self.current_dim == 0 {
-            self.direction *= -1;
+        let epsilon = self.epsilon();
+        self.steps += 1;
+        if self.rng.gen::<f64>() < epsilon {
+            for param in params.iter_mut() {
+                *param += self.rng.gen_range(-self.step_size..self.step_size);
+            }
+            return;
         }
+
+        coordinate_step(params, self.step_size, &mut self.current_dim, &mut self.direction);
     }
 
     fn reset(&mut self) {
         self.current_dim = 0;
         self.direction = 1;
+        self.rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        self.steps = 0;
+    }
+}
+
+/// Evaluates the loss at the given params, for gradient-free optimizers.
+pub type ScalarLoss<'a> = dyn FnMut(&[f64]) -> f64 + 'a;
+
+/// Derivative-free Nelder-Mead simplex search with the standard coefficients
+/// (reflection 1, expansion 2, contraction 0.5, shrink 0.5).
+pub struct NelderMead {
+    initial_step: f64,
+    simplex: Vec<Vec<f64>>,
+    values: Vec<f64>,
+}
+
+impl NelderMead {
+    /// `initial_step` is the offset along each axis used to build the starting simplex around the params.
+    pub fn new(initial_step: f64) -> Self {
+        NelderMead { initial_step, simplex: Vec::new(), values: Vec::new() }
+    }
+
+    /// Runs one simplex iteration, building the `n + 1` point simplex around `params` on the first call.
+    /// Writes the best vertex into `params` and returns its loss.
+    pub fn step_with_loss(&mut self, params: &mut Vec<f64>, loss: &mut ScalarLoss<'_>) -> f64 {
+        if self.simplex.is_empty() {
+            self.simplex.push(params.clone());
+            for i in 0..params.len() {
+                let mut vertex = params.clone();
+                vertex[i] += self.initial_step;
+                self.simplex.push(vertex);
+            }
+            self.values = self.simplex.iter().map(|vertex| loss(vertex)).collect();
+        }
+
+        let mut order: Vec<usize> = (0..self.simplex.len()).collect();
+        order.sort_by(|&a, &b| self.values[a].partial_cmp(&self.values[b]).unwrap());
+        let (best, worst) = (order[0], order[order.len() - 1]);
+        let second_worst = order[order.len().saturating_sub(2)];
+
+        // Centroid of every vertex except the worst
+        let n = params.len();
+        let mut centroid = vec![0.0; n];
+        for &i in &order[..order.len() - 1] {
+            for (c, x) in centroid.iter_mut().zip(&self.simplex[i]) {
+                *c += x / n as f64;
+            }
+        }
+        let towards = |from: &[f64], coefficient: f64| -> Vec<f64> {
+            centroid.iter().zip(from).map(|(c, x)| c + coefficient * (x - c)).collect()
+        };
+
+        let reflected = towards(&self.simplex[worst], -1.0);
+        let reflected_value = loss(&reflected);
+        if reflected_value < self.values[best] {
+            let expanded = towards(&reflected, 2.0);
+            let expanded_value = loss(&expanded);
+            if expanded_value < reflected_value {
+                self.replace(worst, expanded, expanded_value);
+            } else {
+                self.replace(worst, reflected, reflected_value);
+            }
+        } else if reflected_value < self.values[second_worst] {
+            self.replace(worst, reflected, reflected_value);
+        } else {
+            let (contracted, threshold) = if reflected_value < self.values[worst] {
+                (towards(&reflected, 0.5), reflected_value) // Outside contraction
+            } else {
+                (towards(&self.simplex[worst], 0.5), self.values[worst]) // Inside contraction
+            };
+            let contracted_value = loss(&contracted);
+            if contracted_value < threshold {
+                self.replace(worst, contracted, contracted_value);
+            } else {
+                // Shrink every vertex halfway towards the best one
+                let best_vertex = self.simplex[best].clone();
+                for i in 0..self.simplex.len() {
+                    if i != best {
+                        self.simplex[i] = best_vertex.iter().zip(&self.simplex[i]).map(|(b, x)| b + 0.5 * (x - b)).collect();
+                        self.values[i] = loss(&self.simplex[i]);
+                    }
+                }
+            }
+        }
+
+        let best = (0..self.values.len()).min_by(|&a, &b| self.values[a].partial_cmp(&self.values[b]).unwrap()).unwrap();
+        params.clone_from(&self.simplex[best]);
+        self.values[best]
     }
-}
\ No newline at end of file
+
+    /// Discards the simplex; the next step rebuilds it around the params it is given.
+    pub fn reset(&mut self) {
+        self.simplex.clear();
+        self.values.clear();
+    }
+
+    fn replace(&mut self, index: usize, vertex: Vec<f64>, value: f64) {
+        self.simplex[index] = vertex;
+        self.values[index] = value;
+    }
+}
+
+/// Covariance matrix adaptation evolution strategy for black-box minimization, following the
+/// defaults in Hansen's "The CMA Evolution Strategy: A Tutorial".
+pub struct CMAES {
+    sigma: f64,
+    initial_sigma: f64,
+    population_size: Option<usize>,
+    rng: StdRng,
+    seed: Option<u64>,
+    mean: Array1<f64>,
+    covariance: Array2<f64>,
+    eigenvectors: Array2<f64>,
+    eigenvalues_sqrt: Array1<f64>,
+    path_c: Array1<f64>,
+    path_sigma: Array1<f64>,
+    generation: usize,
+}
+
+impl CMAES {
+    /// `sigma` is the initial step size of the sampling distribution around the params.
+    pub fn new(sigma: f64) -> Self {
+        CMAES {
+            sigma,
+            initial_sigma: sigma,
+            population_size: None,
+            rng: StdRng::from_entropy(),
+            seed: None,
+            mean: Array1::zeros(0),
+            covariance: Array2::zeros((0, 0)),
+            eigenvectors: Array2::zeros((0, 0)),
+            eigenvalues_sqrt: Array1::zeros(0),
+            path_c: Array1::zeros(0),
+            path_sigma: Array1::zeros(0),
+            generation: 0,
+        }
+    }
+
+    /// Overrides the default population size of `4 + floor(3 ln n)`; takes effect from the next generation.
+    pub fn set_population_size(&mut self, population_size: usize) {
+        assert!(population_size >= 2, "population size must be at least 2");
+        self.population_size = Some(population_size);
+    }
+
+    /// Seeds the sampling RNG so runs are reproducible; `reset` reseeds with the same value.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Samples and evaluates one generation, then updates the mean, step size and covariance.
+    /// The distribution starts at `params` on the first call; afterwards `params` receives the new mean.
+    /// Returns the best loss in the generation.
+    pub fn step_with_loss(&mut self, params: &mut Vec<f64>, loss: &mut ScalarLoss<'_>) -> f64 {
+        let n = params.len();
+        if self.mean.len() != n {
+            self.mean = Array1::from_vec(params.clone());
+            self.covariance = Array2::eye(n);
+            self.eigenvectors = Array2::eye(n);
+            self.eigenvalues_sqrt = Array1::ones(n);
+            self.path_c = Array1::zeros(n);
+            self.path_sigma = Array1::zeros(n);
+            self.generation = 0;
+        }
+
+        let nf = n as f64;
+        let lambda = self.population_size.unwrap_or(4 + (3.0 * nf.ln()).floor() as usize);
+        let mu = lambda / 2;
+        let raw_weights: Vec<f64> = (1..=mu).map(|i| (mu as f64 + 0.5).ln() - (i as f64).ln()).collect();
+        let weight_sum: f64 = raw_weights.iter().sum();
+        let weights: Vec<f64> = raw_weights.iter().map(|w| w / weight_sum).collect();
+        let mueff = 1.0 / weights.iter().map(|w| w * w).sum::<f64>();
+
+        let cc = (4.0 + mueff / nf) / (nf + 4.0 + 2.0 * mueff / nf);
+        let cs = (mueff + 2.0) / (nf + mueff + 5.0);
+        let c1 = 2.0 / ((nf + 1.3).powi(2) + mueff);
+        let cmu = (1.0 - c1).min(2.0 * (mueff - 2.0 + 1.0 / mueff) / ((nf + 2.0).powi(2) + mueff));
+        let damps = 1.0 + 2.0 * (((mueff - 1.0) / (nf + 1.0)).sqrt() - 1.0).max(0.0) + cs;
+        let chi_n = nf.sqrt() * (1.0 - 1.0 / (4.0 * nf) + 1.0 / (21.0 * nf * nf));
+
+        // Sample y = B D z with z ~ N(0, I), so x = mean + sigma * y ~ N(mean, sigma^2 C)
+        let mut samples: Vec<(f64, Array1<f64>)> = (0..lambda)
+            .map(|_| {
+                let z = Array1::from_shape_fn(n, |_| standard_normal(&mut self.rng));
+                let y = self.eigenvectors.dot(&(&self.eigenvalues_sqrt * &z));
+                let x = &self.mean + &(&y * self.sigma);
+                (loss(x.as_slice().unwrap()), y)
+            })
+            .collect();
+        samples.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        let best_loss = samples[0].0;
+
+        let mut y_w = Array1::zeros(n);
+        for (weight, (_, y)) in weights.iter().zip(&samples) {
+            y_w.scaled_add(*weight, y);
+        }
+        self.mean.scaled_add(self.sigma, &y_w);
+
+        // C^(-1/2) y_w = B D^-1 B^T y_w
+        let whitened = self.eigenvectors.dot(&(self.eigenvectors.t().dot(&y_w) / &self.eigenvalues_sqrt));
+        self.path_sigma = &self.path_sigma * (1.0 - cs) + &(whitened * (cs * (2.0 - cs) * mueff).sqrt());
+        let ps_norm = self.path_sigma.dot(&self.path_sigma).sqrt();
+        self.generation += 1;
+        let stalled_threshold = (1.0 - (1.0 - cs).powi(2 * self.generation as i32)).sqrt() * chi_n * (1.4 + 2.0 / (nf + 1.0));
+        let h_sigma = if ps_norm < stalled_threshold { 1.0 } else { 0.0 };
+        self.path_c = &self.path_c * (1.0 - cc) + &(&y_w * (h_sigma * (cc * (2.0 - cc) * mueff).sqrt()));
+
+        let pc = self.path_c.view().insert_axis(Axis(1));
+        let mut rank_mu = Array2::zeros((n, n));
+        for (weight, (_, y)) in weights.iter().zip(&samples) {
+            let column = y.view().insert_axis(Axis(1));
+            rank_mu.scaled_add(*weight, &column.dot(&column.t()));
+        }
+        let correction = (1.0 - h_sigma) * cc * (2.0 - cc);
+        self.covariance = &self.covariance * (1.0 - c1 - cmu)
+            + &((&pc.dot(&pc.t()) + &(&self.covariance * correction)) * c1)
+            + &(rank_mu * cmu);
+        self.sigma *= ((cs / damps) * (ps_norm / chi_n - 1.0)).exp();
+
+        // Symmetrize against rounding before decomposing; clamp tiny negative eigenvalues to zero
+        self.covariance = (&self.covariance + &self.covariance.t()) / 2.0;
+        let (eigenvalues, eigenvectors) = self.covariance.eigh(UPLO::Lower).unwrap();
+        self.eigenvalues_sqrt = eigenvalues.mapv(|v| v.max(1e-300).sqrt());
+        self.eigenvectors = eigenvectors;
+
+        params.clone_from(&self.mean.to_vec());
+        best_loss
+    }
+
+    /// Discards the distribution and restores the initial step size; the next step restarts around its params.
+    pub fn reset(&mut self) {
+        self.sigma = self.initial_sigma;
+        self.mean = Array1::zeros(0);
+        self.rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+    }
+}
+
+/// Draws a standard normal sample with the Box-Muller transform.
+fn standard_normal(rng: &mut StdRng) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>(); // (0, 1], so the log is finite
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Adaptive gradient clipping (AGC) for one parameter group: rescales `grads` so that
+/// `||grads|| <= clip_factor * max(||params||, eps)`, leaving smaller gradients untouched.
+pub fn adaptive_grad_clip(params: &[f64], grads: &mut [f64], clip_factor: f64, eps: f64) {
+    let param_norm = params.iter().map(|p| p * p).sum::<f64>().sqrt().max(eps);
+    let grad_norm = grads.iter().map(|g| g * g).sum::<f64>().sqrt();
+    let max_norm = clip_factor * param_norm;
+    if grad_norm > max_norm {
+        let scale = max_norm / grad_norm;
+        for grad in grads.iter_mut() {
+            *grad *= scale;
+        }
+    }
+}
+
+pub type GradTransform = Box<dyn Fn(&mut [f64])>;
+
+/// An ordered list of gradient transforms (centralization, clipping, noise, ...) applied before an optimizer step.
+#[derive(Default)]
+pub struct GradPipeline {
+    transforms: Vec<GradTransform>,
+}
+
+impl GradPipeline {
+    pub fn new() -> Self {
+        GradPipeline { transforms: Vec::new() }
+    }
+
+    pub fn push<F: Fn(&mut [f64]) + 'static>(&mut self, transform: F) {
+        self.transforms.push(Box::new(transform));
+    }
+
+    /// Runs every transform on `grads` in insertion order.
+    pub fn apply(&self, grads: &mut [f64]) {
+        for transform in &self.transforms {
+            transform(grads);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+pub struct ReduceLROnPlateau {
+    learning_rate: f64,
+    initial_lr: f64,
+    factor: f64,
+    patience: usize,
+    min_lr: f64,
+    best: f64,
+    counter: usize,
+}
+
+impl ReduceLROnPlateau {
+    pub fn new(learning_rate: f64, factor: f64, patience: usize, min_lr: f64) -> Self {
+        ReduceLROnPlateau {
+            learning_rate,
+            initial_lr: learning_rate,
+            factor,
+            patience,
+            min_lr,
+            best: f64::INFINITY,
+            counter: 0,
+        }
+    }
+
+    /// Records a metric (lower is better) and returns the learning rate to use next.
+    /// After `patience` consecutive non-improving metrics the rate is scaled by `factor`, floored at `min_lr`.
+    pub fn step_metric(&mut self, metric: f64) -> f64 {
+        if metric < self.best {
+            self.best = metric;
+            self.counter = 0;
+        } else {
+            self.counter += 1;
+            if self.counter >= self.patience {
+                self.learning_rate = (self.learning_rate * self.factor).max(self.min_lr);
+                self.counter = 0;
+            }
+        }
+        self.learning_rate
+    }
+
+    pub fn get_lr(&self) -> f64 {
+        self.learning_rate
+    }
+
+    /// Starts over: restores the initial learning rate and forgets the best metric and patience count.
+    pub fn reset(&mut self) {
+        self.learning_rate = self.initial_lr;
+        self.best = f64::INFINITY;
+        self.counter = 0;
+    }
+}
@@ -1,55 +1,156 @@
-use ndarray::{Array1, Array, Axis, IxDyn};
-use ndarray_linalg::solve::Determinant;
+use ndarray::{Array1, Array, ArrayView1, ArrayView2, Axis, Ix1, Ix2, IxDyn};
+use ndarray_linalg::error::LinalgError;
+use ndarray_linalg::eigh::Eigh;
+use ndarray_linalg::svd::SVD;
+use ndarray_linalg::UPLO;
+use ndarray_linalg::solve::{Determinant, Factorize, Inverse, Solve};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
 
+/// Errors returned by the fallible tensor operations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TensorError {
+    /// A float could not be represented as a `usize` (negative, non-finite or too large).
+    InvalidCast(f64),
+    /// The input did not have the rank the operation requires.
+    RankMismatch { expected: usize, actual: usize },
+    /// Two shapes that must agree (matmul inner dimensions, reshape element counts) did not.
+    ShapeMismatch { left: Vec<usize>, right: Vec<usize> },
+    /// The operation needs a square matrix.
+    NonSquare { rows: usize, cols: usize },
+    /// A matrix that had to be inverted was singular.
+    NotInvertible,
+    /// The operation needs a symmetric matrix.
+    NotSymmetric,
+    /// Any other failure reported by the linear-algebra backend.
+    Linalg(String),
+}
+
+impl fmt::Display for TensorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TensorError::InvalidCast(value) => write!(f, "cannot cast {} to usize", value),
+            TensorError::RankMismatch { expected, actual } => write!(f, "expected rank {}, got {}", expected, actual),
+            TensorError::ShapeMismatch { left, right } => write!(f, "incompatible shapes {:?} and {:?}", left, right),
+            TensorError::NonSquare { rows, cols } => write!(f, "expected a square matrix, got {}x{}", rows, cols),
+            TensorError::NotInvertible => write!(f, "matrix is singular"),
+            TensorError::NotSymmetric => write!(f, "matrix is not symmetric"),
+            TensorError::Linalg(message) => write!(f, "linear algebra error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for TensorError {}
+
+impl From<LinalgError> for TensorError {
+    fn from(err: LinalgError) -> Self {
+        match err {
+            LinalgError::NotSquare { rows, cols } => TensorError::NonSquare { rows: rows as usize, cols: cols as usize },
+            other => TensorError::Linalg(other.to_string()),
+        }
+    }
+}
+
+/// Views the input as a square 2D matrix, or reports why it isn't one.
+fn as_square(input: &Array<f64, IxDyn>) -> Result<ArrayView2<'_, f64>, TensorError> {
+    let matrix = as_2d(input)?;
+    let (rows, cols) = matrix.dim();
+    if rows != cols {
+        return Err(TensorError::NonSquare { rows, cols });
+    }
+    Ok(matrix)
+}
 
-/// Computes the softmax of a 1D array.
-pub fn softmax(input: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
-    let input_1d = input.view().into_dimensionality::<ndarray::Ix1>().unwrap(); // Use view to avoid cloning
+/// Views the input as a 1D array, or reports its actual rank.
+fn as_1d(input: &Array<f64, IxDyn>) -> Result<ArrayView1<'_, f64>, TensorError> {
+    input.view().into_dimensionality::<Ix1>().map_err(|_| TensorError::RankMismatch { expected: 1, actual: input.ndim() })
+}
+
+/// Views the input as a 2D array, or reports its actual rank.
+fn as_2d(input: &Array<f64, IxDyn>) -> Result<ArrayView2<'_, f64>, TensorError> {
+    input.view().into_dimensionality::<Ix2>().map_err(|_| TensorError::RankMismatch { expected: 2, actual: input.ndim() })
+}
+
+/// Like `softmax`, but panics on error.
+pub fn softmax_unwrap(input: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
+    softmax(input).unwrap()
+}
+
+/// Computes the softmax of a 1D array, erroring on any other rank.
+pub fn softmax(input: &Array<f64, IxDyn>) -> Result<Array<f64, IxDyn>, TensorError> {
+    let input_1d = as_1d(input)?; // Use view to avoid cloning
     let max = input_1d.iter().cloned().fold(f64::NEG_INFINITY, f64::max); // Numerical stability
     let exp_values: Array1<f64> = input_1d.mapv(|x| (x - max).exp());
     let sum = exp_values.sum();
-    (exp_values / sum).into_dyn()
+    Ok((exp_values / sum).into_dyn())
 }
 
 /// Computes the softmax along a specific axis of a 2D array.
 pub fn softmax_2d(input: &Array<f64, IxDyn>, axis: Axis) -> Array<f64, IxDyn> {
-    let input_2d = input.view().into_dimensionality::<ndarray::Ix2>().unwrap(); // Use view to avoid cloning
+    try_softmax_2d(input, axis).unwrap()
+}
+
+/// Computes the softmax along a specific axis of a 2D array, erroring on any other rank.
+pub fn try_softmax_2d(input: &Array<f64, IxDyn>, axis: Axis) -> Result<Array<f64, IxDyn>, TensorError> {
+    let input_2d = as_2d(input)?; // Use view to avoid cloning
     let mut output = input_2d.to_owned();
     output.map_axis_mut(axis, |mut row| {
         let row_owned = row.to_owned(); // Convert to owned array
-        row.assign(&softmax(&row_owned.into_dyn()));
+        row.assign(&softmax_unwrap(&row_owned.into_dyn())); // Each lane is 1D
     });
-    output.into_dyn()
+    Ok(output.into_dyn())
 }
 
 /// Computes the log-sum-exp of a 1D array.
 pub fn logsumexp(input: &Array<f64, IxDyn>) -> f64 {
-    let input_1d = input.view().into_dimensionality::<ndarray::Ix1>().unwrap(); // Use view to avoid cloning
+    try_logsumexp(input).unwrap()
+}
+
+/// Computes the log-sum-exp of a 1D array, erroring on any other rank.
+pub fn try_logsumexp(input: &Array<f64, IxDyn>) -> Result<f64, TensorError> {
+    let input_1d = as_1d(input)?; // Use view to avoid cloning
     let max = input_1d.iter().cloned().fold(f64::NEG_INFINITY, f64::max); // Numerical stability
     let sum_exp = input_1d.mapv(|x| (x - max).exp()).sum();
-    max + sum_exp.ln()
+    Ok(max + sum_exp.ln())
 }
 
 /// Computes the log-sum-exp along a specific axis of a 2D array.
 pub fn logsumexp_2d(input: &Array<f64, IxDyn>, axis: Axis) -> Array1<f64> {
-    let input_2d = input.view().into_dimensionality::<ndarray::Ix2>().unwrap(); // Use view to avoid cloning
-    input_2d.map_axis(axis, |row| logsumexp(&row.to_owned().into_dyn()))
+    try_logsumexp_2d(input, axis).unwrap()
+}
+
+/// Computes the log-sum-exp along a specific axis of a 2D array, erroring on any other rank.
+pub fn try_logsumexp_2d(input: &Array<f64, IxDyn>, axis: Axis) -> Result<Array1<f64>, TensorError> {
+    let input_2d = as_2d(input)?; // Use view to avoid cloning
+    Ok(input_2d.map_axis(axis, |row| logsumexp(&row.to_owned().into_dyn())))
 }
 
 /// Normalizes a 1D array to have a range of [0, 1].
 pub fn normalize_minmax(input: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
-    let input_1d = input.view().into_dimensionality::<ndarray::Ix1>().unwrap(); // Use view to avoid cloning
+    try_normalize_minmax(input).unwrap()
+}
+
+/// Normalizes a 1D array to have a range of [0, 1], erroring on any other rank.
+pub fn try_normalize_minmax(input: &Array<f64, IxDyn>) -> Result<Array<f64, IxDyn>, TensorError> {
+    let input_1d = as_1d(input)?; // Use view to avoid cloning
     let min = input_1d.iter().cloned().fold(f64::INFINITY, f64::min);
     let max = input_1d.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-    input_1d.mapv(|x| (x - min) / (max - min)).into_dyn()
+    Ok(input_1d.mapv(|x| (x - min) / (max - min)).into_dyn())
 }
 
 /// Normalizes a 1D array to have zero mean and unit variance.
 pub fn normalize_zscore(input: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
-    let input_1d = input.view().into_dimensionality::<ndarray::Ix1>().unwrap(); // Use view to avoid cloning
+    try_normalize_zscore(input).unwrap()
+}
+
+/// Normalizes a 1D array to have zero mean and unit variance, erroring on any other rank.
+pub fn try_normalize_zscore(input: &Array<f64, IxDyn>) -> Result<Array<f64, IxDyn>, TensorError> {
+    let input_1d = as_1d(input)?; // Use view to avoid cloning
     let mean = input_1d.mean().unwrap_or(0.0);
     let std = input_1d.std(0.0);
-    input_1d.mapv(|x| (x - mean) / std).into_dyn()
+    Ok(input_1d.mapv(|x| (x - mean) / std).into_dyn())
 }
 
 /// Sums all elements in the tensor.
@@ -82,9 +183,17 @@ pub fn min_axis(input: &Array<f64, IxDyn>, axis: Axis) -> Array<f64, IxDyn> {
     input.map_axis(axis, |view| *view.iter().min_by(|a, b| a.partial_cmp(b).unwrap()).unwrap()).into_dyn()
 }
 
-/// Reshapes the input tensor to the specified shape.
-pub fn reshape(input: Array<f64, IxDyn>, new_shape: &[usize]) -> Array<f64, IxDyn> {
-    input.into_shape(new_shape).unwrap()
+/// Like `reshape`, but panics on error.
+pub fn reshape_unwrap(input: Array<f64, IxDyn>, new_shape: &[usize]) -> Array<f64, IxDyn> {
+    reshape(input, new_shape).unwrap()
+}
+
+/// Reshapes the input tensor to the specified shape, erroring if the element counts differ.
+pub fn reshape(input: Array<f64, IxDyn>, new_shape: &[usize]) -> Result<Array<f64, IxDyn>, TensorError> {
+    if new_shape.iter().product::<usize>() != input.len() {
+        return Err(TensorError::ShapeMismatch { left: input.shape().to_vec(), right: new_shape.to_vec() });
+    }
+    Ok(input.as_standard_layout().into_owned().into_shape(new_shape).unwrap()) // Standard layout always reshapes
 }
 
 /// Transposes the input tensor, swapping its axes.
@@ -101,13 +210,590 @@ pub fn broadcast(input: &Array<f64, IxDyn>, new_shape: &[usize]) -> Array<f64, I
     input.broadcast(new_shape).unwrap().to_owned()
 }
 
-pub fn dot(input1: &Array<f64, IxDyn>, input2: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
-    let matrix1 = input1.to_owned().into_dimensionality::<ndarray::Ix2>().unwrap();
-    let matrix2 = input2.to_owned().into_dimensionality::<ndarray::Ix2>().unwrap();
-    matrix1.dot(&matrix2).into_dyn()
+/// Returns a standard (row-major, contiguous) layout copy of the input.
+pub fn ascontiguous(input: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
+    input.as_standard_layout().into_owned()
 }
 
-pub fn determinant(input: &Array<f64, IxDyn>) -> f64 {
-    let matrix = input.to_owned().into_dimensionality::<ndarray::Ix2>().unwrap();
-    matrix.det().unwrap()
+/// Generalized product dispatching on rank: 1D·1D gives the scalar inner product (a 0-d array),
+/// 2D·1D and 1D·2D are matrix-vector products, 2D·2D is a matrix product, and 3D·3D multiplies
+/// matching batches over the leading axis. Errors on other rank combinations or mismatched inner
+/// (or batch) dimensions.
+pub fn dot(input1: &Array<f64, IxDyn>, input2: &Array<f64, IxDyn>) -> Result<Array<f64, IxDyn>, TensorError> {
+    let mismatch = || TensorError::ShapeMismatch { left: input1.shape().to_vec(), right: input2.shape().to_vec() };
+    let (a, b) = (ascontiguous(input1), ascontiguous(input2));
+    match (a.ndim(), b.ndim()) {
+        (1, 1) => {
+            let (x, y) = (as_1d(&a)?, as_1d(&b)?);
+            if x.len() != y.len() {
+                return Err(mismatch());
+            }
+            Ok(Array::from_elem(IxDyn(&[]), x.dot(&y)))
+        }
+        (2, 1) => {
+            let (m, v) = (as_2d(&a)?, as_1d(&b)?);
+            if m.ncols() != v.len() {
+                return Err(mismatch());
+            }
+            Ok(m.dot(&v).into_dyn())
+        }
+        (1, 2) => {
+            let (v, m) = (as_1d(&a)?, as_2d(&b)?);
+            if v.len() != m.nrows() {
+                return Err(mismatch());
+            }
+            Ok(v.dot(&m).into_dyn())
+        }
+        (2, 2) => {
+            let (matrix1, matrix2) = (as_2d(&a)?, as_2d(&b)?);
+            if matrix1.ncols() != matrix2.nrows() {
+                return Err(mismatch());
+            }
+            Ok(matrix1.dot(&matrix2).into_dyn())
+        }
+        (3, 3) => {
+            let batch1 = a.view().into_dimensionality::<ndarray::Ix3>().unwrap();
+            let batch2 = b.view().into_dimensionality::<ndarray::Ix3>().unwrap();
+            let ((b1, m, k1), (b2, k2, n)) = (batch1.dim(), batch2.dim());
+            if b1 != b2 || k1 != k2 {
+                return Err(mismatch());
+            }
+            let mut output = ndarray::Array3::zeros((b1, m, n));
+            for ((mut out, x), y) in output.outer_iter_mut().zip(batch1.outer_iter()).zip(batch2.outer_iter()) {
+                out.assign(&x.dot(&y));
+            }
+            Ok(output.into_dyn())
+        }
+        (3, other) | (other, 3) => Err(TensorError::RankMismatch { expected: 3, actual: other }),
+        (1 | 2, other) | (other, _) => Err(TensorError::RankMismatch { expected: 2, actual: other }),
+    }
+}
+
+/// Like `dot`, but panics on error.
+pub fn dot_unwrap(input1: &Array<f64, IxDyn>, input2: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
+    dot(input1, input2).unwrap()
+}
+
+/// Like `determinant`, but panics on error.
+pub fn determinant_unwrap(input: &Array<f64, IxDyn>) -> f64 {
+    determinant(input).unwrap()
+}
+
+/// Computes the determinant of a square 2D matrix, erroring on any other shape.
+pub fn determinant(input: &Array<f64, IxDyn>) -> Result<f64, TensorError> {
+    let contiguous = ascontiguous(input);
+    Ok(as_square(&contiguous)?.det()?)
+}
+
+/// Multiplies a batch `[B, M, K]` by a shared `[K, N]` matrix, producing `[B, M, N]`.
+pub fn batched_matmul_shared(batch: &Array<f64, IxDyn>, weight: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
+    let batch_3d = batch.view().into_dimensionality::<ndarray::Ix3>().unwrap();
+    let weight_2d = weight.view().into_dimensionality::<ndarray::Ix2>().unwrap();
+    let (b, m, k) = batch_3d.dim();
+    let flat = batch_3d.as_standard_layout().into_shape((b * m, k)).unwrap().to_owned(); // Fold the batch into the rows
+    let product = flat.dot(&weight_2d);
+    let n = product.ncols();
+    product.into_shape((b, m, n)).unwrap().into_dyn()
+}
+
+/// Applies RMS normalization over the last axis and scales the result by `gamma`.
+/// Panics if `input` is 0-d or `gamma` isn't a vector as long as the last axis.
+pub fn rms_norm(input: &Array<f64, IxDyn>, gamma: &Array<f64, IxDyn>, epsilon: f64) -> Array<f64, IxDyn> {
+    assert!(input.ndim() >= 1, "rms_norm needs an input with at least one axis");
+    let features = input.shape()[input.ndim() - 1];
+    assert!(
+        gamma.ndim() == 1 && gamma.len() == features,
+        "rms_norm gamma must have shape [{}], got {:?}",
+        features,
+        gamma.shape()
+    );
+    let last = Axis(input.ndim() - 1);
+    let mean_square = input.mapv(|x| x * x).mean_axis(last).unwrap().insert_axis(last);
+    let normalized = input / &mean_square.mapv(|ms| (ms + epsilon).sqrt());
+    normalized * gamma
+}
+
+/// Applies group normalization to an `[N, C, ...]` input, normalizing each group of `C / num_groups` channels.
+pub fn group_norm(input: &Array<f64, IxDyn>, num_groups: usize, gamma: &Array<f64, IxDyn>, beta: &Array<f64, IxDyn>, epsilon: f64) -> Array<f64, IxDyn> {
+    let shape = input.shape().to_vec();
+    assert!(shape.len() >= 2, "group_norm expects an [N, C, ...] input");
+    let (n, c) = (shape[0], shape[1]);
+    assert!(num_groups > 0 && c % num_groups == 0, "channels ({}) must be divisible by num_groups ({})", c, num_groups);
+
+    let group_size = input.len() / (n * num_groups);
+    let grouped = input.as_standard_layout().into_owned().into_shape((n, num_groups, group_size)).unwrap();
+    let mean = grouped.mean_axis(Axis(2)).unwrap().insert_axis(Axis(2));
+    let centered = &grouped - &mean;
+    let var = centered.mapv(|x| x * x).mean_axis(Axis(2)).unwrap().insert_axis(Axis(2));
+    let normalized = (centered / var.mapv(|v| (v + epsilon).sqrt())).into_shape(shape.clone()).unwrap();
+
+    let mut param_shape = vec![1; shape.len()];
+    param_shape[1] = c; // Per-channel affine parameters broadcast over N and the spatial axes
+    let gamma = gamma.view().into_shape(param_shape.clone()).unwrap();
+    let beta = beta.view().into_shape(param_shape).unwrap();
+    normalized * &gamma + &beta
+}
+
+/// Symmetrically quantizes each channel along `axis` to `num_bits` signed integers, returning the values and per-channel scales.
+pub fn quantize_per_channel(input: &Array<f64, IxDyn>, axis: Axis, num_bits: u8) -> (Array<i8, IxDyn>, Array<f64, IxDyn>) {
+    assert!((2..=8).contains(&num_bits), "num_bits must be between 2 and 8, got {}", num_bits);
+    let qmax = ((1i32 << (num_bits - 1)) - 1) as f64;
+    let mut quantized = Array::<i8, IxDyn>::zeros(input.raw_dim());
+    let mut scales = Vec::with_capacity(input.len_of(axis));
+
+    for (i, channel) in input.axis_iter(axis).enumerate() {
+        let max_abs = channel.iter().fold(0.0f64, |acc, &x| acc.max(x.abs()));
+        let scale = if max_abs > 0.0 { max_abs / qmax } else { 1.0 }; // Avoid dividing by zero for all-zero channels
+        quantized.index_axis_mut(axis, i).assign(&channel.mapv(|x| (x / scale).round().clamp(-qmax, qmax) as i8));
+        scales.push(scale);
+    }
+
+    (quantized, Array1::from(scales).into_dyn())
+}
+
+/// Reconstructs floats from per-channel quantized values and their scales.
+pub fn dequantize(quantized: &Array<i8, IxDyn>, scales: &Array<f64, IxDyn>, axis: Axis) -> Array<f64, IxDyn> {
+    let mut output = quantized.mapv(|q| q as f64);
+    for (mut channel, &scale) in output.axis_iter_mut(axis).zip(scales.iter()) {
+        channel *= scale;
+    }
+    output
+}
+
+/// Complementary error function of a scalar (Chebyshev fit, fractional error below 1.2e-7).
+fn erfc_scalar(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.26551223
+        + t * (1.00002368 + t * (0.37409196 + t * (0.09678418 + t * (-0.18628806
+        + t * (0.27886807 + t * (-1.13520398 + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let ans = t * poly.exp();
+    if x >= 0.0 { ans } else { 2.0 - ans }
+}
+
+/// Computes the error function elementwise.
+pub fn erf(input: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
+    input.mapv(|x| 1.0 - erfc_scalar(x))
+}
+
+/// Computes the complementary error function `1 - erf(x)` elementwise.
+pub fn erfc(input: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
+    input.mapv(erfc_scalar)
+}
+
+/// Casts an index array to floats.
+pub fn to_f64(input: &Array<usize, IxDyn>) -> Array<f64, IxDyn> {
+    input.mapv(|x| x as f64)
+}
+
+/// Rounds a float array to the nearest `usize`, erroring on negative, non-finite or out-of-range entries.
+pub fn to_usize_round(input: &Array<f64, IxDyn>) -> Result<Array<usize, IxDyn>, TensorError> {
+    // `usize::MAX as f64` rounds up to 2^64, which is itself out of range
+    if let Some(&bad) = input.iter().find(|x| !x.is_finite() || **x < 0.0 || x.round() >= usize::MAX as f64) {
+        return Err(TensorError::InvalidCast(bad));
+    }
+    Ok(input.mapv(|x| x.round() as usize))
+}
+
+/// Computes the determinant of each matrix in a `[B, N, N]` batch, returning a `[B]` vector.
+pub fn determinant_batched(input: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
+    let batch = input.view().into_dimensionality::<ndarray::Ix3>().unwrap();
+    batch.outer_iter().map(|matrix| matrix.det().unwrap()).collect::<Array1<f64>>().into_dyn()
+}
+
+const TENSOR_MAGIC: &[u8; 4] = b"RAXT";
+/// Largest rank `load_tensor` accepts, so a corrupt header can't request a huge shape vector.
+const MAX_TENSOR_RANK: usize = 32;
+
+/// Writes a tensor as a little-endian binary: magic, rank, shape, then row-major `f64` data.
+pub fn save_tensor(input: &Array<f64, IxDyn>, path: &Path) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(TENSOR_MAGIC)?;
+    writer.write_all(&(input.ndim() as u64).to_le_bytes())?;
+    for &dim in input.shape() {
+        writer.write_all(&(dim as u64).to_le_bytes())?;
+    }
+    for &value in input.iter() { // Logical (row-major) order regardless of memory layout
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    writer.flush()
+}
+
+/// Reads a tensor written by `save_tensor`.
+///
+/// The header is checked against the file size before anything is allocated, so a truncated or
+/// corrupt file yields an `InvalidData` error rather than an oversized allocation.
+pub fn load_tensor(path: &Path) -> io::Result<Array<f64, IxDyn>> {
+    let file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+    if file_len < 12 {
+        return Err(invalid("tensor file is shorter than its header"));
+    }
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != TENSOR_MAGIC {
+        return Err(invalid("not a rax tensor file"));
+    }
+
+    let mut word = [0u8; 8];
+    reader.read_exact(&mut word)?;
+    let ndim = u64::from_le_bytes(word);
+    if ndim > MAX_TENSOR_RANK as u64 {
+        return Err(invalid("tensor rank in header is too large"));
+    }
+    let header_len = 12 + 8 * ndim;
+    if file_len < header_len {
+        return Err(invalid("tensor file is shorter than its header"));
+    }
+    let mut shape = Vec::with_capacity(ndim as usize);
+    for _ in 0..ndim {
+        reader.read_exact(&mut word)?;
+        let dim = usize::try_from(u64::from_le_bytes(word)).map_err(|_| invalid("tensor dimension does not fit in usize"))?;
+        shape.push(dim);
+    }
+
+    let len = shape.iter().try_fold(1usize, |acc, &dim| acc.checked_mul(dim)).ok_or_else(|| invalid("tensor shape overflows usize"))?;
+    if (len as u64).checked_mul(8) != Some(file_len - header_len) {
+        return Err(invalid("tensor data length does not match its shape"));
+    }
+    let mut data = Vec::with_capacity(len);
+    for _ in 0..len {
+        reader.read_exact(&mut word)?;
+        data.push(f64::from_le_bytes(word));
+    }
+    Array::from_shape_vec(shape, data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Computes the NumPy-style broadcast shape of two shapes, if they are compatible.
+fn broadcast_shape(shape1: &[usize], shape2: &[usize]) -> Option<Vec<usize>> {
+    let ndim = shape1.len().max(shape2.len());
+    let mut shape = vec![0; ndim];
+    for i in 0..ndim {
+        let dim1 = if i < ndim - shape1.len() { 1 } else { shape1[i - (ndim - shape1.len())] };
+        let dim2 = if i < ndim - shape2.len() { 1 } else { shape2[i - (ndim - shape2.len())] };
+        shape[i] = match (dim1, dim2) {
+            (a, b) if a == b => a,
+            (1, b) => b,
+            (a, 1) => a,
+            _ => return None,
+        };
+    }
+    Some(shape)
+}
+
+/// Applies `f` elementwise to two arrays after broadcasting them to a common shape.
+fn broadcast_zip(a: &Array<f64, IxDyn>, b: &Array<f64, IxDyn>, f: impl Fn(f64, f64) -> f64) -> Array<f64, IxDyn> {
+    let shape = broadcast_shape(a.shape(), b.shape())
+        .unwrap_or_else(|| panic!("shapes {:?} and {:?} cannot be broadcast together", a.shape(), b.shape()));
+    let a = a.broadcast(shape.clone()).unwrap();
+    let b = b.broadcast(shape).unwrap();
+    ndarray::Zip::from(&a).and(&b).map_collect(|&x, &y| f(x, y))
+}
+
+/// Elementwise maximum of two arrays, with broadcasting.
+pub fn maximum(a: &Array<f64, IxDyn>, b: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
+    broadcast_zip(a, b, f64::max)
+}
+
+/// Elementwise minimum of two arrays, with broadcasting.
+pub fn minimum(a: &Array<f64, IxDyn>, b: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
+    broadcast_zip(a, b, f64::min)
+}
+
+/// Back-propagates through a softmax along `axis`, given its output `s`: `s * (grad - sum(grad * s))`.
+pub fn softmax_backward(output: &Array<f64, IxDyn>, grad_output: &Array<f64, IxDyn>, axis: Axis) -> Array<f64, IxDyn> {
+    let dot = (grad_output * output).sum_axis(axis).insert_axis(axis);
+    output * &(grad_output - &dot)
+}
+
+/// Sums a broadcasted array back down to `target_shape`, reducing over the axes broadcasting expanded.
+pub fn sum_to_shape(input: &Array<f64, IxDyn>, target_shape: &[usize]) -> Array<f64, IxDyn> {
+    let mut result = input.to_owned();
+    while result.ndim() > target_shape.len() {
+        result = result.sum_axis(Axis(0)); // Leading axes added by broadcasting
+    }
+    for (axis, &dim) in target_shape.iter().enumerate() {
+        if dim == 1 && result.shape()[axis] != 1 {
+            result = result.sum_axis(Axis(axis)).insert_axis(Axis(axis));
+        }
+    }
+    assert_eq!(result.shape(), target_shape, "shape {:?} does not broadcast to {:?}", input.shape(), target_shape);
+    result
+}
+
+/// Splits a 1D signal into a `[num_windows, window]` array of windows taken every `stride` samples.
+pub fn sliding_window(input: &Array<f64, IxDyn>, window: usize, stride: usize) -> Array<f64, IxDyn> {
+    let signal = input.view().into_dimensionality::<ndarray::Ix1>().unwrap(); // Use view to avoid cloning
+    assert!(window > 0 && window <= signal.len(), "window ({}) must be between 1 and the signal length ({})", window, signal.len());
+    assert!(stride > 0, "stride must be positive");
+
+    let num_windows = (signal.len() - window) / stride + 1;
+    let mut output = ndarray::Array2::zeros((num_windows, window));
+    for (i, mut row) in output.outer_iter_mut().enumerate() {
+        row.assign(&signal.slice(ndarray::s![i * stride..i * stride + window]));
+    }
+    output.into_dyn()
+}
+
+/// Smooths a 1D series with an exponential moving average: `y[t] = alpha * x[t] + (1 - alpha) * y[t-1]`, `y[0] = x[0]`.
+pub fn ewma(input: &Array<f64, IxDyn>, alpha: f64) -> Array<f64, IxDyn> {
+    let series = input.view().into_dimensionality::<ndarray::Ix1>().unwrap(); // Use view to avoid cloning
+    let mut output = series.to_owned();
+    for t in 1..output.len() {
+        output[t] = alpha * series[t] + (1.0 - alpha) * output[t - 1];
+    }
+    output.into_dyn()
+}
+
+/// Computes the log-sum-exp along `axis` of an array of any rank.
+pub fn logsumexp_axis(input: &Array<f64, IxDyn>, axis: Axis) -> Array<f64, IxDyn> {
+    let max = input.fold_axis(axis, f64::NEG_INFINITY, |&acc, &x| acc.max(x)); // Numerical stability
+    let shift = max.mapv(|m| if m.is_finite() { m } else { 0.0 }).insert_axis(axis); // Avoid inf - inf for all -inf slices
+    let sum_exp = (input - &shift).mapv(f64::exp).sum_axis(axis);
+    sum_exp.mapv(f64::ln) + &shift.index_axis(axis, 0)
+}
+
+/// Solves `A X = B` for a square `[N, N]` matrix `A` and `[N, K]` right-hand sides, factorizing `A` once.
+pub fn solve_multiple(a: &Array<f64, IxDyn>, b: &Array<f64, IxDyn>) -> Result<Array<f64, IxDyn>, LinalgError> {
+    let a_2d = a.view().into_dimensionality::<Ix2>()?;
+    let b_2d = b.view().into_dimensionality::<Ix2>()?;
+    let (rows, cols) = a_2d.dim();
+    if rows != cols {
+        return Err(LinalgError::NotSquare { rows: rows as i32, cols: cols as i32 });
+    }
+    if b_2d.nrows() != rows {
+        return Err(ndarray::ShapeError::from_kind(ndarray::ErrorKind::IncompatibleShape).into());
+    }
+
+    let lu = a_2d.factorize()?;
+    let mut output = ndarray::Array2::zeros(b_2d.raw_dim());
+    for (rhs, mut column) in b_2d.axis_iter(Axis(1)).zip(output.axis_iter_mut(Axis(1))) {
+        column.assign(&lu.solve(&rhs.to_owned())?);
+    }
+    Ok(output.into_dyn())
+}
+
+/// Computes the Kronecker product of two 2D matrices, an `[m * p, n * q]` block matrix of `a[i, j] * b`.
+pub fn kron(a: &Array<f64, IxDyn>, b: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
+    let a_2d = a.view().into_dimensionality::<Ix2>().unwrap();
+    let b_2d = b.view().into_dimensionality::<Ix2>().unwrap();
+    let (p, q) = b_2d.dim();
+    ndarray::Array2::from_shape_fn((a_2d.nrows() * p, a_2d.ncols() * q), |(i, j)| a_2d[[i / p, j / q]] * b_2d[[i % p, j % q]]).into_dyn()
+}
+
+/// Computes the matrix exponential of a square 2D matrix by scaling and squaring with a degree-6
+/// Padé approximant (Golub & Van Loan, Algorithm 11.3.1).
+pub fn expm(input: &Array<f64, IxDyn>) -> Result<Array<f64, IxDyn>, LinalgError> {
+    let matrix = input.view().into_dimensionality::<Ix2>()?;
+    let (rows, cols) = matrix.dim();
+    if rows != cols {
+        return Err(LinalgError::NotSquare { rows: rows as i32, cols: cols as i32 });
+    }
+
+    // Scale so the infinity norm is at most 1/2, where the Padé approximant is accurate
+    let norm = matrix.outer_iter().map(|row| row.iter().map(|x| x.abs()).sum::<f64>()).fold(0.0, f64::max);
+    let squarings = if norm > 0.5 { (norm / 0.5).log2().ceil() as i32 } else { 0 };
+    let scaled = &matrix / 2f64.powi(squarings);
+
+    let q = 6;
+    let identity = ndarray::Array2::<f64>::eye(rows);
+    let mut power = identity.clone();
+    let mut numerator = identity.clone();
+    let mut denominator = identity;
+    let mut coefficient = 1.0;
+    for k in 1..=q {
+        coefficient *= (q - k + 1) as f64 / ((2 * q - k + 1) * k) as f64;
+        power = power.dot(&scaled);
+        numerator.scaled_add(coefficient, &power);
+        denominator.scaled_add(if k % 2 == 0 { coefficient } else { -coefficient }, &power);
+    }
+
+    let mut result = solve_multiple(&denominator.into_dyn(), &numerator.into_dyn())?.into_dimensionality::<Ix2>()?;
+    for _ in 0..squarings {
+        result = result.dot(&result);
+    }
+    Ok(result.into_dyn())
+}
+
+/// Returns the sign and natural log of the absolute determinant, avoiding the overflow of `determinant`
+/// for large matrices. Singular matrices give `(0.0, -inf)`.
+pub fn slogdet(input: &Array<f64, IxDyn>) -> (f64, f64) {
+    let matrix = ascontiguous(input).into_dimensionality::<Ix2>().unwrap();
+    matrix.sln_det().unwrap()
+}
+
+/// Places each 2D block along the diagonal of a larger matrix, with zeros elsewhere.
+pub fn block_diag(blocks: &[Array<f64, IxDyn>]) -> Array<f64, IxDyn> {
+    let blocks: Vec<ArrayView2<f64>> = blocks.iter().map(|block| block.view().into_dimensionality::<Ix2>().unwrap()).collect();
+    let rows = blocks.iter().map(|block| block.nrows()).sum();
+    let cols = blocks.iter().map(|block| block.ncols()).sum();
+
+    let mut result = ndarray::Array2::<f64>::zeros((rows, cols));
+    let (mut row, mut col) = (0, 0);
+    for block in blocks {
+        let (r, c) = block.dim();
+        result.slice_mut(ndarray::s![row..row + r, col..col + c]).assign(&block);
+        row += r;
+        col += c;
+    }
+    result.into_dyn()
+}
+
+/// Computes the gram matrix `X X^T` of a `[N, F]` input, giving the `[N, N]` pairwise inner products.
+pub fn gram_matrix(input: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
+    let matrix = input.view().into_dimensionality::<Ix2>().unwrap();
+    matrix.dot(&matrix.t()).into_dyn()
+}
+
+/// Computes the `[F, F]` covariance of a `[N, F]` data matrix, dividing by `N - ddof`.
+pub fn covariance(input: &Array<f64, IxDyn>, ddof: f64) -> Array<f64, IxDyn> {
+    let matrix = input.view().into_dimensionality::<Ix2>().unwrap();
+    let mean = matrix.mean_axis(Axis(0)).unwrap();
+    let centered = &matrix - &mean;
+    (centered.t().dot(&centered) / (matrix.nrows() as f64 - ddof)).into_dyn()
+}
+
+/// Computes the `[F, F]` Pearson correlation of a `[N, F]` data matrix. A zero-variance feature is
+/// uncorrelated with every other feature (0) and keeps 1 on the diagonal instead of producing NaN.
+pub fn correlation(input: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
+    let cov = covariance(input, 0.0).into_dimensionality::<Ix2>().unwrap();
+    let std = cov.diag().mapv(f64::sqrt);
+    ndarray::Array2::from_shape_fn(cov.raw_dim(), |(i, j)| {
+        if i == j {
+            1.0
+        } else if std[i] == 0.0 || std[j] == 0.0 {
+            0.0
+        } else {
+            (cov[[i, j]] / (std[i] * std[j])).clamp(-1.0, 1.0)
+        }
+    })
+    .into_dyn()
+}
+
+/// Computes the softmax along `axis` together with the log-sum-exp it normalizes by, so losses
+/// needing both don't recompute the exponentials. The LSE has `axis` removed, like `logsumexp_axis`.
+pub fn softmax_with_lse(input: &Array<f64, IxDyn>, axis: Axis) -> (Array<f64, IxDyn>, Array<f64, IxDyn>) {
+    let lse = logsumexp_axis(input, axis);
+    let softmax = (input - &lse.view().insert_axis(axis)).mapv(f64::exp);
+    (softmax, lse)
+}
+
+/// Splits the input along `axis` into consecutive chunks of the given sizes, which must sum to the axis length.
+pub fn split(input: &Array<f64, IxDyn>, sections: &[usize], axis: Axis) -> Result<Vec<Array<f64, IxDyn>>, ndarray::ShapeError> {
+    if axis.index() >= input.ndim() {
+        return Err(ndarray::ShapeError::from_kind(ndarray::ErrorKind::OutOfBounds));
+    }
+    if sections.iter().sum::<usize>() != input.len_of(axis) {
+        return Err(ndarray::ShapeError::from_kind(ndarray::ErrorKind::IncompatibleShape));
+    }
+
+    let mut start = 0;
+    Ok(sections
+        .iter()
+        .map(|&size| {
+            let chunk = input.slice_axis(axis, ndarray::Slice::from(start..start + size)).to_owned();
+            start += size;
+            chunk
+        })
+        .collect())
+}
+
+/// Splits the input along `axis` into `chunks` near-equal parts; the first `len % chunks` parts get one extra element.
+pub fn chunk(input: &Array<f64, IxDyn>, chunks: usize, axis: Axis) -> Vec<Array<f64, IxDyn>> {
+    assert!(chunks > 0, "chunks must be positive");
+    let len = input.len_of(axis);
+    let (base, extra) = (len / chunks, len % chunks);
+    let sections: Vec<usize> = (0..chunks).map(|i| base + usize::from(i < extra)).collect();
+    split(input, &sections, axis).unwrap()
+}
+
+/// Averages along `axis` over only the elements where `mask` is true, e.g. skipping padding.
+/// Masked-out values are never read, so they may be NaN; slices with no selected elements give 0.
+pub fn masked_mean(input: &Array<f64, IxDyn>, mask: &Array<bool, IxDyn>, axis: Axis) -> Array<f64, IxDyn> {
+    assert_eq!(input.shape(), mask.shape(), "mask shape {:?} does not match input shape {:?}", mask.shape(), input.shape());
+    let selected = ndarray::Zip::from(input).and(mask).map_collect(|&x, &keep| if keep { x } else { 0.0 });
+    let counts = mask.mapv(|keep| if keep { 1.0 } else { 0.0 }).sum_axis(axis);
+    ndarray::Zip::from(&selected.sum_axis(axis)).and(&counts).map_collect(|&sum, &count| if count > 0.0 { sum / count } else { 0.0 })
+}
+
+/// Inverts a square 2D matrix, erroring on non-square or singular input.
+pub fn inverse(input: &Array<f64, IxDyn>) -> Result<Array<f64, IxDyn>, TensorError> {
+    let contiguous = ascontiguous(input);
+    match as_square(&contiguous)?.inv() {
+        Ok(inverse) => Ok(inverse.into_dyn()),
+        Err(LinalgError::Lapack(_)) => Err(TensorError::NotInvertible), // getrf/getri only fail on a zero pivot here
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Solves `A x = b` for a square `A`, where `b` is a `[N]` vector or `[N, K]` right-hand sides;
+/// `x` has the shape of `b`. Factorizes `A` rather than forming its inverse.
+pub fn solve(a: &Array<f64, IxDyn>, b: &Array<f64, IxDyn>) -> Array<f64, IxDyn> {
+    try_solve(a, b).unwrap()
+}
+
+/// Like `solve`, but errors on a non-square or singular `A`, or a `b` whose rows don't match.
+pub fn try_solve(a: &Array<f64, IxDyn>, b: &Array<f64, IxDyn>) -> Result<Array<f64, IxDyn>, TensorError> {
+    let rows = as_square(a)?.nrows();
+    let rhs = match b.ndim() {
+        1 => b.view().insert_axis(Axis(1)).to_owned(), // A single column
+        2 => b.clone(),
+        actual => return Err(TensorError::RankMismatch { expected: 2, actual }),
+    };
+    if rhs.shape()[0] != rows {
+        return Err(TensorError::ShapeMismatch { left: a.shape().to_vec(), right: b.shape().to_vec() });
+    }
+
+    let x = match solve_multiple(&ascontiguous(a), &rhs) {
+        Ok(x) => x,
+        Err(LinalgError::Lapack(_)) => return Err(TensorError::NotInvertible), // Zero pivot in the LU factorization
+        Err(err) => return Err(err.into()),
+    };
+    Ok(if b.ndim() == 1 { x.index_axis_move(Axis(1), 0) } else { x })
+}
+
+/// The `(U, s, Vᵀ)` factors returned by `svd`.
+pub type SvdFactors = (Array<f64, IxDyn>, Array1<f64>, Array<f64, IxDyn>);
+
+/// Computes the singular value decomposition `A = U diag(s) Vᵀ` of a `[M, N]` matrix, with singular
+/// values in descending order. With `full_matrices` U is `[M, M]` and Vᵀ is `[N, N]`; otherwise the
+/// thin factors `[M, K]` and `[K, N]` are returned, where `K = min(M, N)`.
+pub fn svd(input: &Array<f64, IxDyn>, full_matrices: bool) -> SvdFactors {
+    try_svd(input, full_matrices).unwrap()
+}
+
+/// Like `svd`, but errors if the input is not 2D or the decomposition fails.
+pub fn try_svd(input: &Array<f64, IxDyn>, full_matrices: bool) -> Result<SvdFactors, TensorError> {
+    let contiguous = ascontiguous(input);
+    let (u, s, vt) = as_2d(&contiguous)?.svd(true, true)?;
+    let (u, vt) = (u.unwrap(), vt.unwrap()); // Both were requested
+    if full_matrices {
+        return Ok((u.into_dyn(), s, vt.into_dyn()));
+    }
+    let k = s.len();
+    Ok((
+        u.slice(ndarray::s![.., ..k]).to_owned().into_dyn(),
+        s,
+        vt.slice(ndarray::s![..k, ..]).to_owned().into_dyn(),
+    ))
+}
+
+/// Computes the eigenvalues (ascending) and eigenvectors (as columns) of a symmetric 2D matrix.
+pub fn eigh(input: &Array<f64, IxDyn>) -> (Array1<f64>, Array<f64, IxDyn>) {
+    try_eigh(input).unwrap()
+}
+
+/// Like `eigh`, but errors if the input is not square or not symmetric to within rounding.
+pub fn try_eigh(input: &Array<f64, IxDyn>) -> Result<(Array1<f64>, Array<f64, IxDyn>), TensorError> {
+    let contiguous = ascontiguous(input);
+    let matrix = as_square(&contiguous)?;
+    let scale = matrix.iter().fold(1.0f64, |acc, x| acc.max(x.abs()));
+    if matrix.indexed_iter().any(|((i, j), &x)| (x - matrix[[j, i]]).abs() > 1e-10 * scale) {
+        return Err(TensorError::NotSymmetric);
+    }
+    let (eigenvalues, eigenvectors) = matrix.eigh(UPLO::Lower)?;
+    Ok((eigenvalues, eigenvectors.into_dyn()))
 }
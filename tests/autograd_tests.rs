@@ -0,0 +1,573 @@
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+    use rax::autograd::{Tensor, add, no_grad, is_grad_enabled, NoGradGuard, mul, sub, div, matmul, relu, sigmoid, tanh, log_softmax, exp, ln, powf, sum, mean, add_scalar, mul_scalar, jacobian};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_map() {
+        let tensor = Tensor::new(array![[1.0, -2.0], [3.0, 0.5]].into_dyn(), true);
+        let squared = tensor.map(|x| x * x);
+        assert_eq!(squared.data, array![[1.0, 4.0], [9.0, 0.25]].into_dyn());
+        assert!(!squared.requires_grad);
+        assert!(squared.creator.is_none());
+        assert_eq!(tensor.data[[0, 1]], -2.0);
+    }
+
+    #[test]
+    fn test_set_requires_grad_false_detaches() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0].into_dyn(), true)));
+        let b = Rc::new(RefCell::new(Tensor::new(array![3.0, 4.0].into_dyn(), true)));
+        let c = add(&a, &b);
+        c.borrow_mut().grad = Some(array![1.0, 1.0].into_dyn());
+        assert!(c.borrow().creator.is_some());
+
+        c.borrow_mut().set_requires_grad(false);
+        let c = c.borrow();
+        assert!(!c.requires_grad);
+        assert!(c.creator.is_none());
+        assert!(c.grad.is_none());
+    }
+
+    #[test]
+    fn test_set_requires_grad_true_enables_recording() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![1.0].into_dyn(), false)));
+        let b = Rc::new(RefCell::new(Tensor::new(array![2.0].into_dyn(), false)));
+        assert!(add(&a, &b).borrow().creator.is_none());
+
+        a.borrow_mut().set_requires_grad(true);
+        let c = add(&a, &b);
+        assert!(c.borrow().requires_grad);
+        assert!(c.borrow().creator.is_some());
+    }
+
+    #[test]
+    fn test_is_leaf() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![1.0].into_dyn(), true)));
+        let b = Rc::new(RefCell::new(Tensor::new(array![2.0].into_dyn(), true)));
+        let c = add(&a, &b);
+        assert!(a.borrow().is_leaf());
+        assert!(!c.borrow().is_leaf());
+    }
+
+    #[test]
+    fn test_backward_leaf_only_drops_intermediate_grads() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0].into_dyn(), true)));
+        let b = Rc::new(RefCell::new(Tensor::new(array![3.0, 4.0].into_dyn(), true)));
+        let c = add(&a, &b);
+        let d = add(&c, &a);
+
+        d.borrow_mut().backward_leaf_only();
+        assert!(c.borrow().grad.is_none());
+        assert!(d.borrow().grad.is_none());
+        assert!(a.borrow().grad.is_some());
+        assert!(b.borrow().grad.is_some());
+    }
+
+    #[test]
+    fn test_reshape() {
+        let tensor = Tensor::new(array![1.0, 2.0, 3.0, 4.0, 5.0, 6.0].into_dyn(), true);
+        let reshaped = tensor.reshape(&[2, 3]);
+        assert_eq!(reshaped.data, array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].into_dyn());
+        assert!(!reshaped.requires_grad);
+        assert_eq!(tensor.data.shape(), &[6]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot reshape")]
+    fn test_reshape_rejects_mismatched_len() {
+        Tensor::new(array![1.0, 2.0, 3.0].into_dyn(), false).reshape(&[2, 2]);
+    }
+
+    #[test]
+    fn test_sgd_update() {
+        let mut tensor = Tensor::new(array![1.0, 2.0, 3.0].into_dyn(), true);
+        tensor.sgd_update(0.1);
+        assert_eq!(tensor.data, array![1.0, 2.0, 3.0].into_dyn());
+
+        tensor.grad = Some(array![10.0, -5.0, 0.0].into_dyn());
+        tensor.sgd_update(0.1);
+        assert!(tensor.data.abs_diff_eq(&array![0.0, 2.5, 3.0].into_dyn(), 1e-12));
+    }
+
+    #[test]
+    fn test_add_backward_reaches_leaves() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![[1.0, 2.0], [3.0, 4.0]].into_dyn(), true)));
+        let b = Rc::new(RefCell::new(Tensor::new(array![[5.0, 6.0], [7.0, 8.0]].into_dyn(), true)));
+        let c = add(&a, &b);
+        c.borrow_mut().backward();
+
+        let ones = ndarray::Array::ones(vec![2, 2]);
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &ones);
+        assert_eq!(b.borrow().grad.as_ref().unwrap(), &ones);
+    }
+
+    #[test]
+    fn test_backward_accumulates_diamond_gradients() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0, 3.0].into_dyn(), true)));
+        let x = Rc::new(RefCell::new(Tensor::new(array![0.5, 0.5, 0.5].into_dyn(), false)));
+        let y = Rc::new(RefCell::new(Tensor::new(array![-1.0, 0.0, 1.0].into_dyn(), false)));
+        let left = add(&a, &x);
+        let right = add(&a, &y);
+        let out = add(&left, &right);
+        out.borrow_mut().backward();
+
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &array![2.0, 2.0, 2.0].into_dyn());
+    }
+
+    #[test]
+    fn test_accumulate_grad() {
+        let mut tensor = Tensor::new(array![0.0, 0.0].into_dyn(), true);
+        tensor.accumulate_grad(&array![1.0, 2.0].into_dyn());
+        tensor.accumulate_grad(&array![0.5, -1.0].into_dyn());
+        assert_eq!(tensor.grad.unwrap(), array![1.5, 1.0].into_dyn());
+    }
+
+    #[test]
+    fn test_backward_reused_tensor_across_paths_of_different_depth() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0].into_dyn(), true)));
+        let b = Rc::new(RefCell::new(Tensor::new(array![3.0, 4.0].into_dyn(), true)));
+        let x = Rc::new(RefCell::new(Tensor::new(array![0.5, 0.5].into_dyn(), false)));
+        let c = add(&a, &b);
+        let d = add(&c, &x); // Longer path: out -> d -> c
+        let out = add(&d, &c); // Shorter path: out -> c
+        out.borrow_mut().backward();
+
+        let twos = array![2.0, 2.0].into_dyn();
+        assert_eq!(c.borrow().grad.as_ref().unwrap(), &twos);
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &twos);
+        assert_eq!(b.borrow().grad.as_ref().unwrap(), &twos);
+        assert!(x.borrow().grad.is_none());
+    }
+
+    #[test]
+    fn test_backward_same_tensor_twice_in_one_op() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![1.0].into_dyn(), true)));
+        let b = Rc::new(RefCell::new(Tensor::new(array![2.0].into_dyn(), true)));
+        let c = add(&a, &b);
+        let out = add(&c, &c);
+        out.borrow_mut().backward();
+
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &array![2.0].into_dyn());
+    }
+
+    #[test]
+    fn test_mul_backward() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0, 3.0].into_dyn(), true)));
+        let b = Rc::new(RefCell::new(Tensor::new(array![4.0, -5.0, 0.5].into_dyn(), true)));
+        let c = mul(&a, &b);
+        assert_eq!(c.borrow().data, array![4.0, -10.0, 1.5].into_dyn());
+        c.borrow_mut().backward();
+
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &b.borrow().data);
+        assert_eq!(b.borrow().grad.as_ref().unwrap(), &a.borrow().data);
+    }
+
+    #[test]
+    fn test_mul_only_records_when_grad_required() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![2.0].into_dyn(), false)));
+        let b = Rc::new(RefCell::new(Tensor::new(array![3.0].into_dyn(), false)));
+        assert!(mul(&a, &b).borrow().creator.is_none());
+
+        b.borrow_mut().set_requires_grad(true);
+        let c = mul(&a, &b);
+        assert!(c.borrow().requires_grad);
+        c.borrow_mut().backward();
+        assert_eq!(b.borrow().grad.as_ref().unwrap(), &array![2.0].into_dyn());
+        assert!(a.borrow().grad.is_none());
+    }
+
+    #[test]
+    fn test_mul_square() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![3.0].into_dyn(), true)));
+        let c = mul(&a, &a);
+        c.borrow_mut().backward();
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &array![6.0].into_dyn());
+    }
+
+    #[test]
+    fn test_sub_backward() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![5.0, 1.0].into_dyn(), true)));
+        let b = Rc::new(RefCell::new(Tensor::new(array![2.0, 3.0].into_dyn(), true)));
+        let c = sub(&a, &b);
+        assert_eq!(c.borrow().data, array![3.0, -2.0].into_dyn());
+        c.borrow_mut().backward();
+
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &array![1.0, 1.0].into_dyn());
+        assert_eq!(b.borrow().grad.as_ref().unwrap(), &array![-1.0, -1.0].into_dyn());
+    }
+
+    #[test]
+    fn test_div_backward() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![6.0, 1.0].into_dyn(), true)));
+        let b = Rc::new(RefCell::new(Tensor::new(array![2.0, 4.0].into_dyn(), true)));
+        let c = div(&a, &b);
+        assert_eq!(c.borrow().data, array![3.0, 0.25].into_dyn());
+        c.borrow_mut().backward();
+
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &array![0.5, 0.25].into_dyn());
+        assert_eq!(b.borrow().grad.as_ref().unwrap(), &array![-1.5, -0.0625].into_dyn());
+    }
+
+    #[test]
+    fn test_div_by_zero_gives_inf() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![1.0].into_dyn(), true)));
+        let b = Rc::new(RefCell::new(Tensor::new(array![0.0].into_dyn(), true)));
+        let c = div(&a, &b);
+        assert_eq!(c.borrow().data[0], f64::INFINITY);
+        c.borrow_mut().backward();
+        assert_eq!(a.borrow().grad.as_ref().unwrap()[0], f64::INFINITY);
+        assert_eq!(b.borrow().grad.as_ref().unwrap()[0], f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_matmul_backward_matches_finite_differences() {
+        let a_data = array![[1.0, 2.0, -1.0], [0.5, 0.0, 3.0]].into_dyn();
+        let b_data = array![[2.0, 1.0], [-1.0, 4.0], [0.0, 0.5]].into_dyn();
+        let a = Rc::new(RefCell::new(Tensor::new(a_data.clone(), true)));
+        let b = Rc::new(RefCell::new(Tensor::new(b_data.clone(), true)));
+        let c = matmul(&a, &b);
+        assert_eq!(c.borrow().data.shape(), &[2, 2]);
+        c.borrow_mut().backward();
+
+        // backward seeds ones, so each gradient is that of sum(a · b)
+        let loss = |a: &ndarray::ArrayD<f64>, b: &ndarray::ArrayD<f64>| rax::tensor::dot_unwrap(a, b).sum();
+        let eps = 1e-6;
+        for (data, grad, is_a) in [(&a_data, a.borrow().grad.clone().unwrap(), true), (&b_data, b.borrow().grad.clone().unwrap(), false)] {
+            assert_eq!(grad.shape(), data.shape());
+            for index in ndarray::indices(data.shape()) {
+                let mut plus = data.clone();
+                let mut minus = data.clone();
+                plus[&index] += eps;
+                minus[&index] -= eps;
+                let numeric = if is_a {
+                    (loss(&plus, &b_data) - loss(&minus, &b_data)) / (2.0 * eps)
+                } else {
+                    (loss(&a_data, &plus) - loss(&a_data, &minus)) / (2.0 * eps)
+                };
+                assert!((grad[&index] - numeric).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_relu_backward_masks_non_positive_inputs() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![-2.0, 0.0, 1.5, 3.0].into_dyn(), true)));
+        let c = relu(&a);
+        assert_eq!(c.borrow().data, array![0.0, 0.0, 1.5, 3.0].into_dyn());
+        c.borrow_mut().grad = Some(array![5.0, 5.0, 5.0, 5.0].into_dyn());
+        c.borrow_mut().backward();
+
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &array![0.0, 0.0, 5.0, 5.0].into_dyn());
+    }
+
+    #[test]
+    fn test_sigmoid_backward() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![0.0, 2.0, -1.0].into_dyn(), true)));
+        let c = sigmoid(&a);
+        assert_eq!(c.borrow().data[0], 0.5);
+        c.borrow_mut().backward();
+
+        let grad = a.borrow().grad.clone().unwrap();
+        assert_eq!(grad[0], 0.25);
+        for (i, x) in [2.0f64, -1.0].iter().enumerate() {
+            let s = 1.0 / (1.0 + (-x).exp());
+            assert!((grad[i + 1] - s * (1.0 - s)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_tanh_backward() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![0.0, 0.5, -2.0].into_dyn(), true)));
+        let c = tanh(&a);
+        c.borrow_mut().backward();
+
+        let grad = a.borrow().grad.clone().unwrap();
+        assert_eq!(grad[0], 1.0);
+        for (i, x) in [0.5f64, -2.0].iter().enumerate() {
+            let cosh = x.cosh();
+            assert!((grad[i + 1] - 1.0 / (cosh * cosh)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_log_softmax_forward_is_stable() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![1000.0, 1000.0].into_dyn(), false)));
+        let c = log_softmax(&a, ndarray::Axis(0));
+        let expected = -(2f64.ln());
+        assert!(c.borrow().data.iter().all(|&x| (x - expected).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_log_softmax_backward_matches_finite_differences() {
+        for (data, axis) in [
+            (array![0.2, -1.0, 3.0].into_dyn(), ndarray::Axis(0)),
+            (array![[1.0, 2.0, -0.5], [0.0, 4.0, 1.0]].into_dyn(), ndarray::Axis(1)),
+            (array![[1.0, 2.0, -0.5], [0.0, 4.0, 1.0]].into_dyn(), ndarray::Axis(0)),
+        ] {
+            let upstream = data.mapv(|x: f64| (1.7 * x).sin()); // Non-uniform so the sum term matters
+            let a = Rc::new(RefCell::new(Tensor::new(data.clone(), true)));
+            let c = log_softmax(&a, axis);
+            c.borrow_mut().grad = Some(upstream.clone());
+            c.borrow_mut().backward();
+            let grad = a.borrow().grad.clone().unwrap();
+
+            // Loss is sum(upstream * log_softmax(x))
+            let loss = |x: &ndarray::ArrayD<f64>| {
+                let t = Rc::new(RefCell::new(Tensor::new(x.clone(), false)));
+                (&log_softmax(&t, axis).borrow().data * &upstream).sum()
+            };
+            let eps = 1e-6;
+            for index in ndarray::indices(data.shape()) {
+                let mut plus = data.clone();
+                let mut minus = data.clone();
+                plus[&index] += eps;
+                minus[&index] -= eps;
+                let numeric = (loss(&plus) - loss(&minus)) / (2.0 * eps);
+                assert!((grad[&index] - numeric).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_exp_backward() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![0.0, 1.0, -2.0].into_dyn(), true)));
+        let c = exp(&a);
+        c.borrow_mut().backward();
+        let expected = array![0.0f64, 1.0, -2.0].mapv(f64::exp).into_dyn();
+        assert!(a.borrow().grad.as_ref().unwrap().abs_diff_eq(&expected, 1e-12));
+    }
+
+    #[test]
+    fn test_ln_backward() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0, 0.25].into_dyn(), true)));
+        let c = ln(&a);
+        assert!((c.borrow().data[1] - 2f64.ln()).abs() < 1e-12);
+        c.borrow_mut().backward();
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &array![1.0, 0.5, 4.0].into_dyn());
+    }
+
+    #[test]
+    fn test_ln_of_zero_is_not_clamped() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![0.0].into_dyn(), true)));
+        let c = ln(&a);
+        assert_eq!(c.borrow().data[0], f64::NEG_INFINITY);
+        c.borrow_mut().backward();
+        assert_eq!(a.borrow().grad.as_ref().unwrap()[0], f64::INFINITY);
+    }
+
+    #[test]
+    fn test_powf_square_backward() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![-1.5, 0.0, 3.0].into_dyn(), true)));
+        let c = powf(&a, 2.0);
+        assert_eq!(c.borrow().data, array![2.25, 0.0, 9.0].into_dyn());
+        c.borrow_mut().backward();
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &array![-3.0, 0.0, 6.0].into_dyn());
+    }
+
+    #[test]
+    fn test_powf_edge_cases() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![0.0, 2.0].into_dyn(), true)));
+        let c = powf(&a, 0.0);
+        c.borrow_mut().backward();
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &array![0.0, 0.0].into_dyn());
+
+        let b = Rc::new(RefCell::new(Tensor::new(array![-4.0].into_dyn(), true)));
+        let d = powf(&b, 0.5);
+        assert!(d.borrow().data[0].is_nan());
+        d.borrow_mut().backward();
+        assert!(b.borrow().grad.as_ref().unwrap()[0].is_nan());
+    }
+
+    #[test]
+    fn test_sum_backward_restores_shape() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![[1.0, 2.0], [3.0, 4.0]].into_dyn(), true)));
+        let loss = sum(&a);
+        assert_eq!(loss.borrow().data, array![10.0].into_dyn());
+        loss.borrow_mut().backward();
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &ndarray::Array::ones(vec![2, 2]));
+
+        let b = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0, 3.0].into_dyn(), true)));
+        let loss = sum(&powf(&b, 2.0));
+        loss.borrow_mut().backward();
+        assert_eq!(b.borrow().grad.as_ref().unwrap(), &array![2.0, 4.0, 6.0].into_dyn());
+    }
+
+    #[test]
+    fn test_mean_backward() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![[1.0, 2.0], [3.0, 6.0]].into_dyn(), true)));
+        let loss = mean(&a);
+        assert_eq!(loss.borrow().data, array![3.0].into_dyn());
+        loss.borrow_mut().backward();
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &ndarray::Array::from_elem(vec![2, 2], 0.25));
+    }
+
+    #[test]
+    fn test_add_broadcast_bias_gradient() {
+        let x = Rc::new(RefCell::new(Tensor::new(array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].into_dyn(), true)));
+        let bias = Rc::new(RefCell::new(Tensor::new(array![0.1, 0.2, 0.3].into_dyn(), true)));
+        let out = add(&x, &bias);
+        assert_eq!(out.borrow().data.shape(), &[2, 3]);
+        out.borrow_mut().grad = Some(array![[1.0, 2.0, 3.0], [10.0, 20.0, 30.0]].into_dyn());
+        out.borrow_mut().backward();
+
+        assert_eq!(bias.borrow().grad.as_ref().unwrap(), &array![11.0, 22.0, 33.0].into_dyn());
+        assert_eq!(x.borrow().grad.as_ref().unwrap().shape(), &[2, 3]);
+    }
+
+    #[test]
+    fn test_mul_broadcast_gradient() {
+        let x = Rc::new(RefCell::new(Tensor::new(array![[1.0, 2.0], [3.0, 4.0]].into_dyn(), true)));
+        let scale = Rc::new(RefCell::new(Tensor::new(array![[2.0], [-1.0]].into_dyn(), true)));
+        let out = mul(&x, &scale);
+        out.borrow_mut().backward();
+
+        assert_eq!(scale.borrow().grad.as_ref().unwrap(), &array![[3.0], [7.0]].into_dyn());
+        assert_eq!(x.borrow().grad.as_ref().unwrap(), &array![[2.0, 2.0], [-1.0, -1.0]].into_dyn());
+    }
+
+    #[test]
+    fn test_detach_stops_gradient_flow() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0].into_dyn(), true)));
+        let b = Rc::new(RefCell::new(Tensor::new(array![3.0, 4.0].into_dyn(), true)));
+        let c = add(&a, &b);
+        let detached = Rc::new(RefCell::new(c.borrow().detach()));
+        assert!(!detached.borrow().requires_grad);
+        assert!(detached.borrow().is_leaf());
+        assert!(c.borrow().creator.is_some());
+
+        let x = Rc::new(RefCell::new(Tensor::new(array![0.5, -1.0].into_dyn(), true)));
+        let out = mul(&detached, &x);
+        out.borrow_mut().backward();
+
+        assert_eq!(x.borrow().grad.as_ref().unwrap(), &array![4.0, 6.0].into_dyn());
+        assert!(a.borrow().grad.is_none());
+        assert!(b.borrow().grad.is_none());
+        assert!(c.borrow().grad.is_none());
+    }
+
+    #[test]
+    fn test_no_grad_skips_graph_construction() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0].into_dyn(), true)));
+        let b = Rc::new(RefCell::new(Tensor::new(array![3.0, 4.0].into_dyn(), true)));
+        let c = no_grad(|| add(&a, &b));
+        assert_eq!(c.borrow().data, array![4.0, 6.0].into_dyn());
+        assert!(c.borrow().creator.is_none());
+        assert!(!c.borrow().requires_grad);
+
+        assert!(is_grad_enabled());
+        assert!(add(&a, &b).borrow().creator.is_some());
+    }
+
+    #[test]
+    fn test_no_grad_guard_nests_and_restores_on_panic() {
+        {
+            let _outer = NoGradGuard::new();
+            {
+                let _inner = NoGradGuard::new();
+                assert!(!is_grad_enabled());
+            }
+            assert!(!is_grad_enabled());
+        }
+        assert!(is_grad_enabled());
+
+        let result = std::panic::catch_unwind(|| no_grad(|| panic!("inside no_grad")));
+        assert!(result.is_err());
+        assert!(is_grad_enabled());
+    }
+
+    #[test]
+    fn test_scalar_ops_backward() {
+        let x = Rc::new(RefCell::new(Tensor::new(array![1.0, -2.0, 0.5].into_dyn(), true)));
+        let scaled = mul_scalar(&x, 3.0);
+        assert_eq!(scaled.borrow().data, array![3.0, -6.0, 1.5].into_dyn());
+        scaled.borrow_mut().backward();
+        assert_eq!(x.borrow().grad.as_ref().unwrap(), &array![3.0, 3.0, 3.0].into_dyn());
+
+        let y = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0].into_dyn(), true)));
+        let shifted = add_scalar(&y, -0.5);
+        assert_eq!(shifted.borrow().data, array![0.5, 1.5].into_dyn());
+        shifted.borrow_mut().backward();
+        assert_eq!(y.borrow().grad.as_ref().unwrap(), &array![1.0, 1.0].into_dyn());
+    }
+
+    #[test]
+    fn test_format() {
+        let tensor = Tensor::new(array![[1.0, -22.5], [1.23456, 0.0]].into_dyn(), false);
+        assert_eq!(tensor.format(2), "[[  1.00, -22.50],\n [  1.23,   0.00]]");
+        assert!(tensor.format(4).contains("1.2346"));
+
+        let vector = Tensor::new(array![0.5, 10.0].into_dyn(), false);
+        assert_eq!(vector.format(1), "[ 0.5, 10.0]");
+    }
+
+    #[test]
+    fn test_jacobian_of_linear_map() {
+        let a = array![[1.0, 2.0, -1.0], [0.5, 0.0, 3.0]].into_dyn();
+        let matrix = Rc::new(RefCell::new(Tensor::new(a.clone(), false)));
+        let x = Rc::new(RefCell::new(Tensor::new(array![[0.3], [-1.2], [2.0]].into_dyn(), true)));
+        let jac = jacobian(|x| matmul(&matrix, x), &x);
+        assert_eq!(jac, a);
+        assert!(x.borrow().grad.is_none());
+
+        let y = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0].into_dyn(), true)));
+        let squared = jacobian(|y| mul(y, y), &y);
+        assert_eq!(squared, array![[2.0, 0.0], [0.0, 4.0]].into_dyn());
+    }
+
+    #[test]
+    fn test_sub_broadcast_gradient() {
+        let x = Rc::new(RefCell::new(Tensor::new(array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].into_dyn(), true)));
+        let shift = Rc::new(RefCell::new(Tensor::new(array![0.5, 1.0, 1.5].into_dyn(), true)));
+        let out = sub(&x, &shift);
+        assert_eq!(out.borrow().data, array![[0.5, 1.0, 1.5], [3.5, 4.0, 4.5]].into_dyn());
+        out.borrow_mut().backward();
+
+        assert_eq!(shift.borrow().grad.as_ref().unwrap(), &array![-2.0, -2.0, -2.0].into_dyn());
+        assert_eq!(x.borrow().grad.as_ref().unwrap(), &ndarray::Array::ones(vec![2, 3]));
+    }
+
+    #[test]
+    fn test_div_broadcast_gradient() {
+        let x = Rc::new(RefCell::new(Tensor::new(array![[1.0, 2.0, 3.0], [4.0, 6.0, 9.0]].into_dyn(), true)));
+        let scale = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0, 3.0].into_dyn(), true)));
+        let out = div(&x, &scale);
+        out.borrow_mut().backward();
+
+        // d/ds (x / s) = -x / s^2, summed over the broadcast rows
+        assert_eq!(scale.borrow().grad.as_ref().unwrap(), &array![-5.0, -2.0, -4.0 / 3.0].into_dyn());
+        assert_eq!(x.borrow().grad.as_ref().unwrap(), &array![[1.0, 0.5, 1.0 / 3.0], [1.0, 0.5, 1.0 / 3.0]].into_dyn());
+    }
+
+    #[test]
+    fn test_repeated_backward_accumulates_leaf_grads_once() {
+        let a = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0].into_dyn(), true)));
+        let b = Rc::new(RefCell::new(Tensor::new(array![3.0, 4.0].into_dyn(), false)));
+        let product = mul(&a, &b);
+        let loss = sum(&product);
+
+        loss.borrow_mut().backward();
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &array![3.0, 4.0].into_dyn());
+        loss.borrow_mut().backward();
+        assert_eq!(a.borrow().grad.as_ref().unwrap(), &array![6.0, 8.0].into_dyn());
+        assert_eq!(product.borrow().grad.as_ref().unwrap(), &array![1.0, 1.0].into_dyn());
+    }
+
+    #[test]
+    fn test_jacobian_leaves_outside_intermediates_without_grad() {
+        let p = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0].into_dyn(), true)));
+        let w = add_scalar(&p, 1.0);
+        let x = Rc::new(RefCell::new(Tensor::new(array![0.5, -1.0].into_dyn(), true)));
+        jacobian(|x| mul(x, &w), &x);
+        assert!(w.borrow().grad.is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "matmul expects 2D tensors, got ranks 1 and 2")]
+    fn test_matmul_rejects_non_2d_operand() {
+        let v = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0].into_dyn(), true)));
+        let m = Rc::new(RefCell::new(Tensor::new(array![[1.0, 0.0], [0.0, 1.0]].into_dyn(), true)));
+        matmul(&v, &m);
+    }
+}
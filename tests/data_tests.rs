@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+    use rax::data::{from_csv, shuffle_indices, kfold_indices, stratified_split, polynomial_features, PCA, kmeans, mixup, mixup_labels};
+    use std::path::PathBuf;
+
+    fn write_temp(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("rax_{}_{}.csv", name, std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_from_csv() {
+        let with_header = write_temp("header", "x,y,z\n1,2,3\n4.5, -5 ,6e1\n");
+        let without_header = write_temp("no_header", "1,2,3\n4.5,-5,60\n");
+        let expected = array![[1.0, 2.0, 3.0], [4.5, -5.0, 60.0]].into_dyn();
+
+        assert_eq!(from_csv(&with_header, true).unwrap(), expected);
+        assert_eq!(from_csv(&without_header, false).unwrap(), expected);
+        assert!(from_csv(&with_header, false).is_err()); // Header parsed as data
+        std::fs::remove_file(with_header).unwrap();
+        std::fs::remove_file(without_header).unwrap();
+    }
+
+    #[test]
+    fn test_from_csv_rejects_ragged_rows() {
+        let path = write_temp("ragged", "1,2,3\n4,5\n");
+        let err = from_csv(&path, false).unwrap_err();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("expected 3 columns, got 2"));
+    }
+
+    #[test]
+    fn test_shuffle_indices() {
+        let first = shuffle_indices(50, 7);
+        assert_eq!(first, shuffle_indices(50, 7));
+        assert_ne!(first, shuffle_indices(50, 8));
+        assert_ne!(first, (0..50).collect::<Vec<_>>());
+
+        let mut sorted = first.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..50).collect::<Vec<_>>());
+        assert!(shuffle_indices(0, 1).is_empty());
+    }
+
+    #[test]
+    fn test_kfold_indices_partition_samples() {
+        let folds = kfold_indices(10, 3, 42);
+        assert_eq!(folds.len(), 3);
+
+        let mut all_val: Vec<usize> = folds.iter().flat_map(|(_, val)| val.clone()).collect();
+        all_val.sort();
+        assert_eq!(all_val, (0..10).collect::<Vec<_>>());
+
+        for (train, val) in &folds {
+            assert_eq!(train.len() + val.len(), 10);
+            assert!(val.iter().all(|i| !train.contains(i)));
+        }
+        assert_eq!(folds.iter().map(|(_, val)| val.len()).collect::<Vec<_>>(), vec![4, 3, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be between")]
+    fn test_kfold_indices_rejects_k_above_n() {
+        kfold_indices(3, 4, 0);
+    }
+
+    #[test]
+    fn test_stratified_split_preserves_class_ratio() {
+        let labels = ndarray::Array::from_iter((0..100).map(|i| usize::from(i % 5 == 0))).into_dyn(); // 20% positives
+        let (train, test) = stratified_split(&labels, 0.25, 3);
+        assert_eq!(train.len() + test.len(), 100);
+        assert!(test.iter().all(|i| !train.contains(i)));
+
+        let positive_ratio = |split: &[usize]| split.iter().filter(|&&i| labels[i] == 1).count() as f64 / split.len() as f64;
+        assert!((positive_ratio(&train) - 0.2).abs() < 0.02);
+        assert!((positive_ratio(&test) - 0.2).abs() < 0.02);
+        assert_eq!(stratified_split(&labels, 0.25, 3), (train, test));
+    }
+
+    #[test]
+    fn test_polynomial_features() {
+        let input = array![[2.0, 3.0], [-1.0, 0.5]].into_dyn();
+        let expanded = polynomial_features(&input, 2, true);
+        let expected = array![
+            [1.0, 2.0, 3.0, 4.0, 6.0, 9.0],
+            [1.0, -1.0, 0.5, 1.0, -0.5, 0.25]
+        ].into_dyn();
+        assert_eq!(expanded, expected);
+
+        let without_bias = polynomial_features(&input, 2, false);
+        assert_eq!(without_bias.shape(), &[2, 5]);
+        assert_eq!(polynomial_features(&input, 3, true).shape(), &[2, 10]);
+    }
+
+    #[test]
+    fn test_pca_reconstruction() {
+        // Points scattered tightly around the line y = 2x
+        let data = array![[0.0, 0.1], [1.0, 1.9], [2.0, 4.1], [3.0, 5.9], [4.0, 8.05]].into_dyn();
+        let mut pca = PCA::new();
+        pca.fit(&data, 1);
+        assert_eq!(pca.components.shape(), &[1, 2]);
+
+        let projected = pca.transform(&data);
+        assert_eq!(projected.shape(), &[5, 1]);
+
+        let reconstructed = pca.inverse_transform(&projected);
+        assert_eq!(reconstructed.shape(), data.shape());
+        let max_error = (&reconstructed - &data).iter().fold(0.0f64, |acc, x| acc.max(x.abs()));
+        assert!(max_error < 0.1);
+    }
+
+    #[test]
+    fn test_kmeans_separated_blobs() {
+        let data = array![
+            [0.0, 0.1], [0.2, -0.1], [-0.1, 0.0], [0.1, 0.2],
+            [10.0, 10.1], [9.8, 10.0], [10.2, 9.9], [10.1, 10.2]
+        ]
+        .into_dyn();
+        let (centroids, assignments) = kmeans(&data, 2, 100, 7);
+        assert_eq!(centroids.shape(), &[2, 2]);
+        assert_eq!(assignments.shape(), &[8]);
+
+        let first = assignments[0];
+        assert!(assignments.iter().take(4).all(|&a| a == first));
+        assert!(assignments.iter().skip(4).all(|&a| a != first));
+        assert!((centroids[[first, 0]] - 0.05).abs() < 1e-9);
+        assert!((centroids[[1 - first, 0]] - 10.025).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mixup() {
+        let x1 = array![[1.0, 2.0], [3.0, 4.0]].into_dyn();
+        let x2 = array![[3.0, 0.0], [-1.0, 8.0]].into_dyn();
+        assert_eq!(mixup(&x1, &x2, 1.0), x1);
+        assert_eq!(mixup(&x1, &x2, 0.5), array![[2.0, 1.0], [1.0, 6.0]].into_dyn());
+
+        let labels1 = array![0usize, 2].into_dyn();
+        let labels2 = array![1usize, 2].into_dyn();
+        let targets = mixup_labels(&labels1, &labels2, 3, 0.7);
+        assert!(targets.abs_diff_eq(&array![[0.7, 0.3, 0.0], [0.0, 0.0, 1.0]].into_dyn(), 1e-12));
+    }
+
+    #[test]
+    fn test_kmeans_zero_iters_assigns_to_initial_centroids() {
+        let data = array![[0.0, 0.0], [0.1, 0.0], [5.0, 5.0], [5.1, 5.0]].into_dyn();
+        let (centroids, assignments) = kmeans(&data, 2, 0, 3);
+        assert!(assignments.iter().all(|&a| a < 2));
+        for (row, &cluster) in data.outer_iter().zip(assignments.iter()) {
+            let distance = |c: usize| (&centroids.index_axis(ndarray::Axis(0), c) - &row).mapv(|x| x * x).sum();
+            assert!(distance(cluster) <= distance(1 - cluster));
+        }
+    }
+}
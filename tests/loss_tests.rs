@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+    use rax::autograd::Tensor;
+    use rax::nn::loss::{cross_entropy_from_probs, nll_loss, mse_loss, cross_entropy};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use rax::tensor::{logsumexp, logsumexp_axis, softmax_2d};
+
+    #[test]
+    fn test_cross_entropy_from_probs_matches_logits() {
+        let logits = array![[2.0, 1.0, 0.1], [0.5, 2.5, -1.0]].into_dyn();
+        let targets = array![0usize, 2].into_dyn();
+        let probs = softmax_2d(&logits, ndarray::Axis(1));
+
+        let from_logits = logits
+            .outer_iter()
+            .zip(targets.iter())
+            .map(|(row, &t)| logsumexp(&row.to_owned()) - row[t])
+            .sum::<f64>()
+            / 2.0;
+        assert!((cross_entropy_from_probs(&probs, &targets) - from_logits).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cross_entropy_from_probs_clamps_zero() {
+        let probs = array![[1.0, 0.0]].into_dyn();
+        let loss = cross_entropy_from_probs(&probs, &array![1usize].into_dyn());
+        assert!(loss.is_finite());
+        assert!((loss - 1e12f64.ln()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_nll_loss_of_log_softmax_matches_cross_entropy() {
+        let logits = array![[2.0, 1.0, 0.1], [0.5, 2.5, -1.0], [-3.0, 0.0, 3.0]].into_dyn();
+        let targets = array![0usize, 2, 1].into_dyn();
+        let lse = logsumexp_axis(&logits, ndarray::Axis(1));
+        let log_probs = &logits - &lse.clone().insert_axis(ndarray::Axis(1));
+
+        let fused = targets.iter().enumerate().map(|(i, &t)| lse[i] - logits[[i, t]]).sum::<f64>() / 3.0;
+        assert!((nll_loss(&log_probs, &targets) - fused).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_mse_loss_gradient() {
+        let pred = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0, 4.0, 0.0].into_dyn(), true)));
+        let target = Rc::new(RefCell::new(Tensor::new(array![0.0, 2.0, 1.0, 1.0].into_dyn(), true)));
+        let loss = mse_loss(&pred, &target);
+        assert_eq!(loss.borrow().data, array![(1.0 + 0.0 + 9.0 + 1.0) / 4.0].into_dyn());
+        loss.borrow_mut().backward();
+
+        assert_eq!(pred.borrow().grad.as_ref().unwrap(), &array![0.5, 0.0, 1.5, -0.5].into_dyn());
+        assert!(target.borrow().grad.is_none());
+    }
+
+    #[test]
+    fn test_mse_loss_sgd_regression() {
+        let pred = Rc::new(RefCell::new(Tensor::new(array![0.0, 5.0, -3.0].into_dyn(), true)));
+        let target = Rc::new(RefCell::new(Tensor::new(array![1.0, 2.0, 0.0].into_dyn(), false)));
+        let mut previous = f64::INFINITY;
+        for _ in 0..5 {
+            pred.borrow_mut().zero_grad();
+            let loss = mse_loss(&pred, &target);
+            let value = loss.borrow().data[0];
+            assert!(value < previous);
+            previous = value;
+            loss.borrow_mut().backward();
+            pred.borrow_mut().sgd_update(0.5);
+        }
+    }
+
+    #[test]
+    fn test_cross_entropy_loss_and_gradient() {
+        let logits = Rc::new(RefCell::new(Tensor::new(array![[1.0, 2.0, 3.0], [0.0, 0.0, 0.0]].into_dyn(), true)));
+        let targets = array![2usize, 0].into_dyn();
+        let loss = cross_entropy(&logits, &targets);
+
+        // Row 0: softmax([1, 2, 3]) = [0.0900, 0.2447, 0.6652]; row 1 is uniform
+        let p: Vec<f64> = [1.0f64, 2.0, 3.0].iter().map(|x| x.exp() / (1f64.exp() + 2f64.exp() + 3f64.exp())).collect();
+        let expected_loss = (-p[2].ln() + 3f64.ln()) / 2.0;
+        assert!((loss.borrow().data[0] - expected_loss).abs() < 1e-12);
+
+        loss.borrow_mut().backward();
+        let third = 1.0 / 3.0;
+        let expected_grad = array![[p[0], p[1], p[2] - 1.0], [third - 1.0, third, third]].into_dyn() / 2.0;
+        assert!(logits.borrow().grad.as_ref().unwrap().abs_diff_eq(&expected_grad, 1e-12));
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use rax::metrics::{AverageMeter, topk_accuracy, roc_auc, mae, mape};
+
+    #[test]
+    fn test_average_meter_weights_by_batch_size() {
+        let mut meter = AverageMeter::new();
+        assert_eq!(meter.average(), 0.0);
+
+        meter.update(0.5, 10);
+        meter.update(1.0, 30);
+        meter.update(0.0, 10);
+        assert!((meter.average() - 35.0 / 50.0).abs() < 1e-12);
+
+        meter.reset();
+        assert_eq!(meter.average(), 0.0);
+    }
+
+    #[test]
+    fn test_topk_accuracy() {
+        let logits = array![[0.1, 2.0, 1.5, -1.0], [3.0, 0.0, 0.5, 0.2]].into_dyn();
+        let targets = array![2usize, 0].into_dyn(); // First target is the 2nd-highest logit
+        assert_eq!(topk_accuracy(&logits, &targets, 1), 0.5);
+        assert_eq!(topk_accuracy(&logits, &targets, 2), 1.0);
+    }
+
+    #[test]
+    fn test_roc_auc_separable() {
+        let scores = array![0.1, 0.4, 0.35, 0.8, 0.9].into_dyn();
+        let labels = array![0usize, 0, 0, 1, 1].into_dyn();
+        assert_eq!(roc_auc(&scores, &labels), 1.0);
+        assert_eq!(roc_auc(&scores.mapv(|s| -s), &labels), 0.0);
+    }
+
+    #[test]
+    fn test_roc_auc_ties_and_random() {
+        let tied = array![0.5, 0.5, 0.5, 0.5].into_dyn();
+        assert_eq!(roc_auc(&tied, &array![0usize, 1, 0, 1].into_dyn()), 0.5);
+
+        let mut rng = StdRng::seed_from_u64(11);
+        let scores = ndarray::Array::from_iter((0..2000).map(|_| rng.gen::<f64>())).into_dyn();
+        let labels = ndarray::Array::from_iter((0..2000).map(|_| rng.gen_range(0..2usize))).into_dyn();
+        assert!((roc_auc(&scores, &labels) - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_mae() {
+        let pred = array![3.0, -0.5, 2.0, 7.0].into_dyn();
+        let target = array![2.5, 0.0, 2.0, 8.0].into_dyn();
+        assert!((mae(&pred, &target) - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_mape_skips_zero_targets() {
+        let pred = array![110.0, 45.0, 3.0].into_dyn();
+        let target = array![100.0, 50.0, 0.0].into_dyn();
+        assert!((mape(&pred, &target) - 10.0).abs() < 1e-12); // (10% + 10%) / 2
+        assert_eq!(mape(&pred, &array![0.0, 0.0, 0.0].into_dyn()), 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "roc_auc labels must be 0 or 1, got 2")]
+    fn test_roc_auc_rejects_non_binary_labels() {
+        roc_auc(&array![0.1, 0.6, 0.9].into_dyn(), &array![0usize, 1, 2].into_dyn());
+    }
+}
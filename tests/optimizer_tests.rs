@@ -0,0 +1,386 @@
+#[cfg(test)]
+mod tests {
+    use rax::optimizer::{Optimizer, SGD, Adam, Momentum, adaptive_grad_clip, GradPipeline, GridSearch, SimpleRandomSearch, RMSprop, AdaGrad, NelderMead, CMAES, LARS};
+
+    #[test]
+    fn test_sgd_grad_clip_bounds_update() {
+        let mut sgd = SGD::new(0.1);
+        sgd.set_grad_clip(Some(1.0));
+        let mut params = vec![0.0, 0.0, 0.0];
+        sgd.step(&mut params, &[1000.0, -50.0, 0.5]);
+        assert_eq!(params, vec![-0.1, 0.1, -0.05]);
+    }
+
+    #[test]
+    fn test_sgd_without_grad_clip_is_unchanged() {
+        let mut sgd = SGD::new(0.1);
+        let mut params = vec![0.0];
+        sgd.step(&mut params, &[1000.0]);
+        assert_eq!(params, vec![-100.0]);
+    }
+
+    #[test]
+    fn test_adam_grad_clip_matches_clipped_gradients() {
+        let mut clipped = Adam::new(0.01, 0.9, 0.999, 1e-8);
+        clipped.set_grad_clip(Some(2.0));
+        let mut reference = Adam::new(0.01, 0.9, 0.999, 1e-8);
+
+        let mut params = vec![1.0, 1.0];
+        let mut expected = vec![1.0, 1.0];
+        for grads in [[100.0, -0.5], [-7.0, 3.0], [0.1, 50.0]] {
+            clipped.step(&mut params, &grads);
+            let bounded: Vec<f64> = grads.iter().map(|g| g.clamp(-2.0, 2.0)).collect();
+            reference.step(&mut expected, &bounded);
+        }
+        assert_eq!(params, expected);
+    }
+
+    #[test]
+    fn test_sgd_last_update_cosine_detects_oscillation() {
+        let mut sgd = SGD::new(0.1);
+        sgd.set_track_update_cosine(true);
+        let mut params = vec![0.0, 0.0];
+        sgd.step(&mut params, &[1.0, 2.0]);
+        assert_eq!(sgd.last_update_cosine(), None);
+
+        sgd.step(&mut params, &[-1.0, -2.0]);
+        assert!((sgd.last_update_cosine().unwrap() + 1.0).abs() < 1e-12);
+        sgd.step(&mut params, &[-2.0, -4.0]);
+        assert!((sgd.last_update_cosine().unwrap() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_momentum_last_update_cosine_detects_oscillation() {
+        let mut momentum = Momentum::new(0.1, 0.5);
+        momentum.set_track_update_cosine(true);
+        let mut params = vec![0.0, 0.0];
+        for grads in [[1.0, -1.0], [-3.0, 3.0], [3.0, -3.0]] {
+            momentum.step(&mut params, &grads);
+        }
+        assert!(momentum.last_update_cosine().unwrap() < 0.0);
+
+        momentum.reset();
+        assert_eq!(momentum.last_update_cosine(), None);
+    }
+
+    #[test]
+    fn test_last_update_cosine_disabled_by_default() {
+        let mut sgd = SGD::new(0.1);
+        let mut params = vec![0.0];
+        sgd.step(&mut params, &[1.0]);
+        sgd.step(&mut params, &[-1.0]);
+        assert_eq!(sgd.last_update_cosine(), None);
+    }
+
+    #[test]
+    fn test_adaptive_grad_clip_scales_relative_to_params() {
+        let params = [3.0, 4.0]; // ||params|| = 5
+        let mut grads = [6.0, 8.0]; // ||grads|| = 10
+        adaptive_grad_clip(&params, &mut grads, 0.1, 1e-3);
+        assert!((grads[0] - 0.3).abs() < 1e-12 && (grads[1] - 0.4).abs() < 1e-12);
+
+        let mut small = [0.03, 0.04];
+        adaptive_grad_clip(&params, &mut small, 0.1, 1e-3);
+        assert_eq!(small, [0.03, 0.04]);
+
+        let mut from_zero = [3.0, 4.0];
+        adaptive_grad_clip(&[0.0, 0.0], &mut from_zero, 0.5, 1e-2);
+        let norm = (from_zero[0] * from_zero[0] + from_zero[1] * from_zero[1]).sqrt();
+        assert!((norm - 0.5 * 1e-2).abs() < 1e-12); // eps floors the parameter norm
+    }
+
+    #[test]
+    fn test_adam_moment_snapshot() {
+        let (beta1, beta2) = (0.9, 0.99);
+        let mut adam = Adam::new(0.01, beta1, beta2, 1e-8);
+        assert_eq!(adam.moment_snapshot(), (vec![], vec![]));
+
+        let grads = [[1.0, -2.0], [3.0, 0.5], [-1.0, 1.0]];
+        let mut params = vec![0.0, 0.0];
+        let (mut m, mut v) = ([0.0; 2], [0.0; 2]);
+        for g in grads.iter() {
+            adam.step(&mut params, g);
+            for i in 0..2 {
+                m[i] = beta1 * m[i] + (1.0 - beta1) * g[i];
+                v[i] = beta2 * v[i] + (1.0 - beta2) * g[i] * g[i];
+            }
+        }
+
+        let (m_hat, v_hat) = adam.moment_snapshot();
+        for i in 0..2 {
+            assert!((m_hat[i] - m[i] / (1.0 - beta1.powi(3))).abs() < 1e-12);
+            assert!((v_hat[i] - v[i] / (1.0 - beta2.powi(3))).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_adam_soft_reset_keeps_step() {
+        let mut adam = Adam::new(0.01, 0.9, 0.999, 1e-8);
+        let mut params = vec![1.0, 2.0];
+        adam.step(&mut params, &[0.5, -0.5]);
+        adam.step(&mut params, &[0.2, 0.1]);
+
+        adam.soft_reset();
+        assert_eq!(adam.current_step(), 2);
+        assert_eq!(adam.moment_snapshot(), (vec![0.0, 0.0], vec![0.0, 0.0]));
+
+        adam.reset();
+        assert_eq!(adam.current_step(), 0);
+    }
+
+    #[test]
+    fn test_grad_pipeline_applies_in_order() {
+        let mut pipeline = GradPipeline::new();
+        pipeline.push(|grads: &mut [f64]| {
+            let mean = grads.iter().sum::<f64>() / grads.len() as f64;
+            grads.iter_mut().for_each(|g| *g -= mean);
+        });
+        pipeline.push(|grads: &mut [f64]| grads.iter_mut().for_each(|g| *g = g.clamp(-1.0, 1.0)));
+
+        let mut grads = [4.0, 1.0, 1.0];
+        pipeline.apply(&mut grads); // Centered to [2, -1, -1], then clipped
+        assert_eq!(grads, [1.0, -1.0, -1.0]);
+
+        let mut sgd = SGD::new(0.5);
+        let mut params = vec![0.0, 0.0, 0.0];
+        sgd.step(&mut params, &grads);
+        assert_eq!(params, vec![-0.5, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_step_closure_matches_step() {
+        // Loss is sum(p^2), so grads are 2p
+        let mut closure = |params: &[f64]| (params.iter().map(|p| p * p).sum(), params.iter().map(|p| 2.0 * p).collect());
+
+        let mut direct = SGD::new(0.1);
+        let mut direct_params = vec![1.0, -2.0, 0.5];
+        let mut closure_sgd = SGD::new(0.1);
+        let mut closure_params = direct_params.clone();
+
+        for _ in 0..3 {
+            let grads: Vec<f64> = direct_params.iter().map(|p| 2.0 * p).collect();
+            let expected_loss: f64 = direct_params.iter().map(|p| p * p).sum();
+            direct.step(&mut direct_params, &grads);
+            assert_eq!(closure_sgd.step_closure(&mut closure_params, &mut closure), expected_loss);
+            assert_eq!(closure_params, direct_params);
+        }
+    }
+
+    #[test]
+    fn test_grid_search_epsilon_decay() {
+        let mut search = GridSearch::new(0.1);
+        search.set_seed(3);
+        search.set_epsilon_decay(0.02);
+        assert_eq!(search.epsilon(), 1.0);
+
+        // A coordinate step moves exactly one param; a random jump moves both
+        let mut params = vec![0.0, 0.0];
+        let mut jumps = Vec::new();
+        for _ in 0..300 {
+            let before = params.clone();
+            let epsilon = search.epsilon();
+            search.step(&mut params, &[0.0, 0.0]);
+            assert!(search.epsilon() < epsilon);
+            jumps.push(params.iter().zip(&before).all(|(a, b)| a != b));
+        }
+        let early = jumps[..50].iter().filter(|&&jump| jump).count();
+        let late = jumps[250..].iter().filter(|&&jump| jump).count();
+        assert!(early > late);
+        assert!(late < 5);
+    }
+
+    #[test]
+    fn test_random_search_seeded_reset_is_reproducible() {
+        let mut search = SimpleRandomSearch::new(0.5);
+        search.set_seed(9);
+        search.set_epsilon_decay(0.1);
+        let mut first = vec![1.0, -1.0];
+        for _ in 0..10 {
+            search.step(&mut first, &[]);
+        }
+
+        search.reset();
+        let mut second = vec![1.0, -1.0];
+        for _ in 0..10 {
+            search.step(&mut second, &[]);
+        }
+        assert_eq!(first, second);
+        assert!(search.epsilon() < 1.0);
+    }
+
+    /// After three warm-up steps and a reset of `[1, 3]`, the next update at those indices should match
+    /// a fresh optimizer's first update, and the others should match an optimizer that was never reset.
+    fn assert_reset_indices<O: Optimizer>(make: impl Fn() -> O, reset: impl Fn(&mut O)) {
+        let grads = [0.5, -1.0, 2.0, 0.3];
+        let next_grads = [1.0, 0.2, -0.4, 2.0];
+        let (mut reset_opt, mut reference, mut fresh) = (make(), make(), make());
+        let mut reset_params = vec![1.0, 2.0, 3.0, 4.0];
+        let mut reference_params = reset_params.clone();
+        for _ in 0..3 {
+            reset_opt.step(&mut reset_params, &grads);
+            reference.step(&mut reference_params, &grads);
+        }
+        reset(&mut reset_opt);
+
+        let before = reset_params.clone();
+        reset_opt.step(&mut reset_params, &next_grads);
+        let reference_before = reference_params.clone();
+        reference.step(&mut reference_params, &next_grads);
+        let mut fresh_params = vec![0.0; 4];
+        fresh.step(&mut fresh_params, &next_grads);
+
+        for i in 0..4 {
+            let delta = reset_params[i] - before[i];
+            let expected = if i == 1 || i == 3 { fresh_params[i] } else { reference_params[i] - reference_before[i] };
+            assert!((delta - expected).abs() < 1e-12, "index {}: {} vs {}", i, delta, expected);
+        }
+    }
+
+    #[test]
+    fn test_reset_indices() {
+        assert_reset_indices(|| Momentum::new(0.1, 0.9), |opt| opt.reset_indices(&[1, 3]));
+        assert_reset_indices(|| RMSprop::new(0.01, 0.9, 1e-8), |opt| opt.reset_indices(&[1, 3]));
+        assert_reset_indices(|| AdaGrad::new(0.1, 1e-8), |opt| opt.reset_indices(&[1, 3]));
+
+        let mut adam = Adam::new(0.001, 0.9, 0.999, 1e-8);
+        let mut params = vec![1.0, 2.0, 3.0, 4.0];
+        adam.step(&mut params, &[0.5, -1.0, 2.0, 0.3]);
+        adam.reset_indices(&[1, 3]);
+        let (m_hat, v_hat) = adam.moment_snapshot();
+        assert!(m_hat[0] != 0.0 && m_hat[2] != 0.0 && v_hat[0] != 0.0 && v_hat[2] != 0.0);
+        assert_eq!((m_hat[1], m_hat[3], v_hat[1], v_hat[3]), (0.0, 0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_nelder_mead_minimizes_bowl() {
+        let mut bowl = |p: &[f64]| (p[0] - 1.0).powi(2) + 2.0 * (p[1] + 2.0).powi(2);
+        let mut optimizer = NelderMead::new(0.5);
+        let mut params = vec![4.0, 3.0];
+        let mut loss = f64::INFINITY;
+        for _ in 0..200 {
+            let next = optimizer.step_with_loss(&mut params, &mut bowl);
+            assert!(next <= loss);
+            loss = next;
+        }
+        assert!(loss < 1e-10);
+        assert!((params[0] - 1.0).abs() < 1e-4 && (params[1] + 2.0).abs() < 1e-4);
+
+        optimizer.reset();
+        let mut restarted = vec![0.0, 0.0];
+        let restarted_loss = optimizer.step_with_loss(&mut restarted, &mut bowl); // Simplex rebuilt around the new start
+        assert!(restarted_loss <= bowl(&[0.0, 0.0]) && restarted_loss > 1.0);
+    }
+
+    #[test]
+    fn test_cmaes_minimizes_sphere() {
+        let mut sphere = |p: &[f64]| p.iter().map(|x| x * x).sum::<f64>();
+        let mut optimizer = CMAES::new(1.0);
+        optimizer.set_seed(42);
+        optimizer.set_population_size(10);
+        let mut params = vec![3.0, -2.0, 1.5, 4.0];
+        let start = sphere(&params);
+
+        let losses: Vec<f64> = (0..150).map(|_| optimizer.step_with_loss(&mut params, &mut sphere)).collect();
+        let window_means: Vec<f64> = losses.chunks(30).map(|w| w.iter().sum::<f64>() / w.len() as f64).collect();
+        assert!(window_means.windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(window_means[0] < start);
+        assert!(sphere(&params) < 1e-6);
+
+        // The same seed reproduces the same run
+        optimizer.reset();
+        let mut replay = vec![3.0, -2.0, 1.5, 4.0];
+        assert_eq!(optimizer.step_with_loss(&mut replay, &mut sphere), losses[0]);
+    }
+
+    #[test]
+    fn test_adam_effective_lr() {
+        let mut adam = Adam::new(0.01, 0.9, 0.999, 1e-8);
+        assert!(adam.effective_lr().is_empty());
+
+        let mut params = vec![1.0, 2.0];
+        adam.step(&mut params, &[0.5, -2.0]);
+        adam.step(&mut params, &[0.1, 1.0]);
+
+        // v_t = 0.999^2 * 0 + 0.999 * 0.001 * g1^2 + 0.001 * g2^2, bias-corrected by 1 - 0.999^2
+        let expected: Vec<f64> = [(0.5f64, 0.1f64), (-2.0, 1.0)]
+            .iter()
+            .map(|(g1, g2)| {
+                let v = 0.999 * 0.001 * g1 * g1 + 0.001 * g2 * g2;
+                let v_hat = v / (1.0 - 0.999f64.powi(2));
+                0.01 / (v_hat.sqrt() + 1e-8)
+            })
+            .collect();
+        for (actual, expected) in adam.effective_lr().iter().zip(&expected) {
+            assert!((actual - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_lars_trust_ratio() {
+        let mut lars = LARS::new(0.1, 0.9, 0.01);
+        let mut params = vec![3.0, 4.0]; // ||w|| = 5
+        let grads = [0.6, 0.8]; // ||g|| = 1
+
+        // local_lr = 0.1 * 0.01 * 5 / 1
+        lars.step(&mut params, &grads);
+        let local_lr = 0.1 * 0.01 * 5.0;
+        let velocity = [local_lr * 0.6, local_lr * 0.8];
+        assert!((params[0] - (3.0 - velocity[0])).abs() < 1e-12);
+        assert!((params[1] - (4.0 - velocity[1])).abs() < 1e-12);
+
+        // The second step recomputes the ratio from the updated params and adds momentum
+        let before = params.clone();
+        let norm = (before[0] * before[0] + before[1] * before[1]).sqrt();
+        lars.step(&mut params, &grads);
+        let local_lr = 0.1 * 0.01 * norm;
+        for i in 0..2 {
+            let expected_velocity = 0.9 * velocity[i] + local_lr * grads[i];
+            assert!((params[i] - (before[i] - expected_velocity)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "grad_clip must be non-negative")]
+    fn test_sgd_rejects_negative_grad_clip() {
+        SGD::new(0.1).set_grad_clip(Some(-1.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "grad_clip must be non-negative")]
+    fn test_adam_rejects_nan_grad_clip() {
+        Adam::new(0.01, 0.9, 0.999, 1e-8).set_grad_clip(Some(f64::NAN));
+    }
+
+    #[test]
+    fn test_random_search_directed_move_needs_no_gradients() {
+        let mut search = SimpleRandomSearch::new(0.5);
+        search.set_seed(4);
+        search.set_epsilon_decay(1e6);
+        let mut params = vec![0.0, 0.0];
+        search.step(&mut params, &[]); // epsilon starts at 1, so the first step is a jump
+
+        // Afterwards epsilon is ~0 and each step sweeps one coordinate, as GridSearch does
+        let start = params.clone();
+        search.step(&mut params, &[]);
+        assert_eq!(params, vec![start[0] + 0.5, start[1]]);
+        search.step(&mut params, &[]);
+        assert_eq!(params, vec![start[0] + 0.5, start[1] + 0.5]);
+        search.step(&mut params, &[]);
+        assert_eq!(params, vec![start[0], start[1] + 0.5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "epsilon decay rate must be non-negative")]
+    fn test_grid_search_rejects_negative_epsilon_decay() {
+        GridSearch::new(0.1).set_epsilon_decay(-0.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "reset index 4 is out of range for 4 params")]
+    fn test_reset_indices_rejects_out_of_range_index() {
+        let mut momentum = Momentum::new(0.1, 0.9);
+        let mut params = vec![1.0, 2.0, 3.0, 4.0];
+        momentum.step(&mut params, &[0.5, -1.0, 2.0, 0.3]);
+        momentum.reset_indices(&[1, 4]);
+    }
+}
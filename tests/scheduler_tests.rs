@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod tests {
+    use rax::scheduler::ReduceLROnPlateau;
+
+    #[test]
+    fn test_reduce_lr_on_plateau() {
+        let mut scheduler = ReduceLROnPlateau::new(0.1, 0.5, 2, 0.02);
+        let losses = [1.0, 0.8, 0.7, 0.7, 0.75, 0.72, 0.71, 0.69, 0.7, 0.7, 0.7, 0.7];
+        let lrs: Vec<f64> = losses.iter().map(|&loss| scheduler.step_metric(loss)).collect();
+
+        assert_eq!(&lrs[..4], &[0.1, 0.1, 0.1, 0.1]);
+        assert!((lrs[4] - 0.05).abs() < 1e-12); // Second non-improving metric triggers the drop
+        assert!((lrs[6] - 0.025).abs() < 1e-12);
+        assert!((lrs[7] - 0.025).abs() < 1e-12); // 0.69 is a new best
+        assert!((lrs[9] - 0.02).abs() < 1e-12); // Floored at min_lr
+        assert_eq!(scheduler.get_lr(), 0.02);
+    }
+
+    #[test]
+    fn test_reduce_lr_on_plateau_reset_restores_initial_lr() {
+        let mut scheduler = ReduceLROnPlateau::new(0.1, 0.5, 1, 0.0);
+        scheduler.step_metric(1.0);
+        scheduler.step_metric(1.0);
+        assert!((scheduler.get_lr() - 0.05).abs() < 1e-12);
+
+        scheduler.reset();
+        assert_eq!(scheduler.get_lr(), 0.1);
+        assert_eq!(scheduler.step_metric(5.0), 0.1); // Any metric is a new best after reset
+    }
+}
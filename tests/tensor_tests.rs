@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     use ndarray::array;
-    use rax::tensor::{dot, determinant};
+    use rax::tensor::{softmax_unwrap, dot_unwrap, determinant_unwrap, batched_matmul_shared, rms_norm, group_norm, quantize_per_channel, dequantize, erf, erfc, to_f64, to_usize_round, TensorError, determinant_batched, ascontiguous, transpose, softmax, try_logsumexp_2d, dot, save_tensor, load_tensor, maximum, minimum, softmax_backward, softmax_2d, sum_to_shape, sliding_window, ewma, logsumexp_axis, logsumexp, solve_multiple, kron, expm, slogdet, block_diag, gram_matrix, covariance, correlation, softmax_with_lse, split, chunk, masked_mean, determinant, reshape, inverse, solve, try_solve, svd, eigh, try_eigh};
 
     #[test]
     fn it_works() {
@@ -13,7 +13,7 @@ mod tests {
     fn test_dot() {
         let input1 = array![[1.0, 2.0], [3.0, 4.0]].into_dyn();
         let input2 = array![[5.0, 6.0], [7.0, 8.0]].into_dyn();
-        let result = dot(&input1, &input2);
+        let result = dot_unwrap(&input1, &input2);
         let expected = array![[19.0, 22.0], [43.0, 50.0]].into_dyn();
         assert!(result.abs_diff_eq(&expected, 1e-6));
     }
@@ -21,8 +21,578 @@ mod tests {
     #[test]
     fn test_determinant() {
         let input = array![[1.0, 2.0], [3.0, 4.0]].into_dyn();
-        let result = determinant(&input);
+        let result = determinant_unwrap(&input);
         let expected = -2.0;
         assert!((result - expected).abs() < 1e-6);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_batched_matmul_shared() {
+        let batch = array![[[1.0, 2.0], [3.0, 4.0]], [[5.0, 6.0], [7.0, 8.0]]].into_dyn();
+        let weight = array![[1.0, 0.5, -1.0], [2.0, 0.0, 1.0]].into_dyn();
+        let result = batched_matmul_shared(&batch, &weight);
+        assert_eq!(result.shape(), &[2, 2, 3]);
+        for b in 0..2 {
+            let sample = batch.index_axis(ndarray::Axis(0), b).to_owned();
+            let expected = dot_unwrap(&sample, &weight);
+            assert!(result.index_axis(ndarray::Axis(0), b).abs_diff_eq(&expected, 1e-12));
+        }
+    }
+
+    #[test]
+    fn test_rms_norm() {
+        let input = array![[1.0, 2.0, 3.0, 4.0], [-2.0, 0.5, 6.0, 1.0]].into_dyn();
+        let gamma = ndarray::Array::ones(4).into_dyn();
+        let result = rms_norm(&input, &gamma, 1e-12);
+        for row in result.outer_iter() {
+            let rms = (row.mapv(|x| x * x).sum() / row.len() as f64).sqrt();
+            assert!((rms - 1.0).abs() < 1e-6);
+        }
+
+        let scaled = rms_norm(&input, &array![2.0, 2.0, 2.0, 2.0].into_dyn(), 1e-12);
+        assert!(scaled.abs_diff_eq(&(&result * 2.0), 1e-12));
+    }
+
+    #[test]
+    fn test_group_norm() {
+        let input = array![[[1.0, 2.0], [3.0, 4.0], [10.0, 20.0], [30.0, 50.0]]].into_dyn();
+        let gamma = ndarray::Array::ones(4).into_dyn();
+        let beta = ndarray::Array::zeros(4).into_dyn();
+        let result = group_norm(&input, 2, &gamma, &beta, 1e-12);
+        assert_eq!(result.shape(), &[1, 4, 2]);
+
+        let flat: Vec<f64> = result.iter().cloned().collect();
+        for group in flat.chunks(4) {
+            let mean = group.iter().sum::<f64>() / 4.0;
+            let var = group.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / 4.0;
+            assert!(mean.abs() < 1e-9);
+            assert!((var - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_group_norm_rejects_indivisible_channels() {
+        let input = ndarray::Array::zeros(vec![1, 3, 2]);
+        let gamma = ndarray::Array::ones(3).into_dyn();
+        let beta = ndarray::Array::zeros(3).into_dyn();
+        group_norm(&input, 2, &gamma, &beta, 1e-5);
+    }
+
+    #[test]
+    fn test_quantize_per_channel_round_trip() {
+        let input = array![[0.1, -0.5, 0.9], [10.0, -3.0, 7.5]].into_dyn();
+        let (quantized, scales) = quantize_per_channel(&input, ndarray::Axis(0), 8);
+        assert_eq!(scales.shape(), &[2]);
+        assert_eq!(quantized[[1, 0]], 127);
+
+        let restored = dequantize(&quantized, &scales, ndarray::Axis(0));
+        for (row, (original, reconstructed)) in input.outer_iter().zip(restored.outer_iter()).enumerate() {
+            for (x, y) in original.iter().zip(reconstructed.iter()) {
+                assert!((x - y).abs() <= scales[row] / 2.0 + 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_erf_known_values() {
+        let input = array![0.0, 0.5, -1.0, 6.0].into_dyn();
+        let result = erf(&input);
+        let expected = array![0.0, 0.5204998778, -0.8427007929, 1.0].into_dyn();
+        assert!(result.abs_diff_eq(&expected, 1e-6));
+    }
+
+    #[test]
+    fn test_erfc_complements_erf() {
+        let input = array![-2.0, -0.3, 0.0, 1.7, 4.0].into_dyn();
+        let sum = erf(&input) + erfc(&input);
+        assert!(sum.abs_diff_eq(&ndarray::Array::ones(5).into_dyn(), 1e-12));
+        assert!((erfc(&array![1.0].into_dyn())[0] - 0.1572992071).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_to_f64() {
+        let input = array![[0usize, 3], [7, 42]].into_dyn();
+        let expected = array![[0.0, 3.0], [7.0, 42.0]].into_dyn();
+        assert_eq!(to_f64(&input), expected);
+    }
+
+    #[test]
+    fn test_to_usize_round() {
+        let input = array![0.2, 1.5, 2.49, 9.9].into_dyn();
+        assert_eq!(to_usize_round(&input).unwrap(), array![0usize, 2, 2, 10].into_dyn());
+
+        let negative = array![1.0, -0.5].into_dyn();
+        assert_eq!(to_usize_round(&negative), Err(TensorError::InvalidCast(-0.5)));
+
+        let infinite = array![f64::INFINITY].into_dyn();
+        assert_eq!(to_usize_round(&infinite), Err(TensorError::InvalidCast(f64::INFINITY)));
+
+        let too_large = array![1e30].into_dyn();
+        assert_eq!(to_usize_round(&too_large), Err(TensorError::InvalidCast(1e30)));
+    }
+
+    #[test]
+    fn test_determinant_batched() {
+        let input = array![[[1.0, 2.0], [3.0, 4.0]], [[2.0, 0.0], [1.0, 5.0]]].into_dyn();
+        let result = determinant_batched(&input);
+        assert_eq!(result.shape(), &[2]);
+        for (b, matrix) in input.outer_iter().enumerate() {
+            assert!((result[b] - determinant_unwrap(&matrix.to_owned())).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_ascontiguous() {
+        let input = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].into_dyn();
+        let transposed = transpose(input.clone(), None);
+        assert!(!transposed.is_standard_layout());
+
+        let contiguous = ascontiguous(&transposed);
+        assert!(contiguous.is_standard_layout());
+        assert_eq!(contiguous, transposed);
+    }
+
+    #[test]
+    fn test_dot_on_transposed_input() {
+        let input = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].into_dyn();
+        let result = dot_unwrap(&transpose(input.clone(), None), &input);
+        let expected = array![[17.0, 22.0, 27.0], [22.0, 29.0, 36.0], [27.0, 36.0, 45.0]].into_dyn();
+        assert!(result.abs_diff_eq(&expected, 1e-12));
+        assert!((determinant_unwrap(&transpose(array![[1.0, 2.0], [3.0, 4.0]].into_dyn(), None)) + 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_try_variants_report_rank_mismatch() {
+        let cube = ndarray::Array::zeros(vec![2, 2, 2]);
+        let err = softmax(&cube).unwrap_err();
+        assert_eq!(err, TensorError::RankMismatch { expected: 1, actual: 3 });
+        assert_eq!(err.to_string(), "expected rank 1, got 3");
+
+        let vector = array![1.0, 2.0].into_dyn();
+        assert_eq!(try_logsumexp_2d(&vector, ndarray::Axis(0)).unwrap_err().to_string(), "expected rank 2, got 1");
+        let tensor4 = ndarray::Array::zeros(vec![1, 1, 1, 1]);
+        assert_eq!(dot(&tensor4, &vector), Err(TensorError::RankMismatch { expected: 2, actual: 4 }));
+        assert_eq!(dot(&cube, &vector), Err(TensorError::RankMismatch { expected: 3, actual: 1 }));
+    }
+
+    #[test]
+    fn test_softmax_unwrap_matches_softmax() {
+        let input = array![1.0, 2.0, 3.0].into_dyn();
+        assert_eq!(softmax(&input).unwrap(), softmax_unwrap(&input));
+    }
+
+    #[test]
+    fn test_save_and_load_tensor() {
+        let input = array![[1.5, -2.0, 3.25], [0.1, 1e-300, f64::MAX]].into_dyn();
+        let path = std::env::temp_dir().join(format!("rax_tensor_{}.bin", std::process::id()));
+        save_tensor(&input, &path).unwrap();
+        let loaded = load_tensor(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.shape(), &[2, 3]);
+        assert_eq!(loaded, input);
+    }
+
+    #[test]
+    fn test_maximum_minimum() {
+        let a = array![1.0, 5.0, -3.0].into_dyn();
+        let b = array![2.0, 4.0, -3.0].into_dyn();
+        assert_eq!(maximum(&a, &b), array![2.0, 5.0, -3.0].into_dyn());
+        assert_eq!(minimum(&a, &b), array![1.0, 4.0, -3.0].into_dyn());
+    }
+
+    #[test]
+    fn test_maximum_minimum_broadcast() {
+        let a = array![[1.0, 5.0, -3.0], [0.0, -1.0, 8.0]].into_dyn();
+        let b = array![0.5, 0.5, 0.5].into_dyn();
+        assert_eq!(maximum(&a, &b), array![[1.0, 5.0, 0.5], [0.5, 0.5, 8.0]].into_dyn());
+        assert_eq!(minimum(&b, &a), array![[0.5, 0.5, -3.0], [0.0, -1.0, 0.5]].into_dyn());
+    }
+
+    #[test]
+    fn test_softmax_backward_matches_finite_difference() {
+        let input = array![[0.2, -1.0, 0.7], [1.5, 0.3, -0.4]].into_dyn();
+        let weights = array![[1.0, -2.0, 0.5], [0.3, 0.9, -1.1]].into_dyn();
+        let loss = |x: &ndarray::Array<f64, ndarray::IxDyn>| (softmax_2d(x, ndarray::Axis(1)) * &weights).sum();
+
+        let output = softmax_2d(&input, ndarray::Axis(1));
+        let analytic = softmax_backward(&output, &weights, ndarray::Axis(1));
+
+        let h = 1e-6;
+        for idx in [[0, 0], [0, 2], [1, 1], [1, 2]] {
+            let mut plus = input.clone();
+            let mut minus = input.clone();
+            plus[idx.as_slice()] += h;
+            minus[idx.as_slice()] -= h;
+            let numeric = (loss(&plus) - loss(&minus)) / (2.0 * h);
+            assert!((analytic[idx.as_slice()] - numeric).abs() < 1e-7);
+        }
+    }
+
+    #[test]
+    fn test_sum_to_shape() {
+        let input = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].into_dyn();
+        assert_eq!(sum_to_shape(&input, &[3]), array![5.0, 7.0, 9.0].into_dyn());
+        assert_eq!(sum_to_shape(&input, &[1, 3]), array![[5.0, 7.0, 9.0]].into_dyn());
+        assert_eq!(sum_to_shape(&input, &[2, 1]), array![[6.0], [15.0]].into_dyn());
+
+        let scalar = sum_to_shape(&input, &[]);
+        assert_eq!(scalar.shape(), &[] as &[usize]);
+        assert_eq!(scalar.sum(), 21.0);
+    }
+
+    #[test]
+    fn test_sliding_window() {
+        let signal = array![1.0, 2.0, 3.0, 4.0, 5.0].into_dyn();
+        let windows = sliding_window(&signal, 3, 1);
+        assert_eq!(windows, array![[1.0, 2.0, 3.0], [2.0, 3.0, 4.0], [3.0, 4.0, 5.0]].into_dyn());
+        assert_eq!(sliding_window(&signal, 2, 2), array![[1.0, 2.0], [3.0, 4.0]].into_dyn());
+    }
+
+    #[test]
+    #[should_panic(expected = "window")]
+    fn test_sliding_window_rejects_oversized_window() {
+        sliding_window(&array![1.0, 2.0].into_dyn(), 3, 1);
+    }
+
+    #[test]
+    fn test_ewma() {
+        let constant = array![3.0, 3.0, 3.0, 3.0].into_dyn();
+        assert!(ewma(&constant, 0.3).abs_diff_eq(&constant, 1e-12));
+
+        let step = array![0.0, 1.0, 1.0, 1.0].into_dyn();
+        let smoothed = ewma(&step, 0.5);
+        assert!(smoothed.abs_diff_eq(&array![0.0, 0.5, 0.75, 0.875].into_dyn(), 1e-12));
+    }
+
+    #[test]
+    fn test_logsumexp_axis_3d() {
+        let input = ndarray::Array::from_shape_fn(vec![2, 3, 4], |idx| (idx[0] * 12 + idx[1] * 4 + idx[2]) as f64 * 0.7 - 3.0);
+        let result = logsumexp_axis(&input, ndarray::Axis(1));
+        assert_eq!(result.shape(), &[2, 4]);
+        for i in 0..2 {
+            for k in 0..4 {
+                let slice = ndarray::Array::from_iter((0..3).map(|j| input[[i, j, k]])).into_dyn();
+                assert!((result[[i, k]] - logsumexp(&slice)).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_logsumexp_axis_is_stable() {
+        let input = array![[1000.0, 1000.0], [-1000.0, f64::NEG_INFINITY]].into_dyn();
+        let result = logsumexp_axis(&input, ndarray::Axis(1));
+        assert!((result[0] - (1000.0 + 2f64.ln())).abs() < 1e-9);
+        assert!((result[1] + 1000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_solve_multiple() {
+        let a = array![[3.0, 1.0], [1.0, 2.0]].into_dyn();
+        let b = array![[9.0, 1.0], [8.0, -3.0]].into_dyn();
+        let x = solve_multiple(&a, &b).unwrap();
+        assert_eq!(x.shape(), &[2, 2]);
+
+        let residual = dot_unwrap(&a, &x) - &b;
+        for column in residual.axis_iter(ndarray::Axis(1)) {
+            assert!(column.iter().all(|r| r.abs() < 1e-10));
+        }
+        assert!(x.abs_diff_eq(&array![[2.0, 1.0], [3.0, -2.0]].into_dyn(), 1e-10));
+    }
+
+    #[test]
+    fn test_solve_multiple_rejects_bad_shapes() {
+        let rect = ndarray::Array::zeros(vec![2, 3]);
+        assert!(solve_multiple(&rect, &ndarray::Array::zeros(vec![2, 1])).is_err());
+        let a = array![[1.0, 0.0], [0.0, 1.0]].into_dyn();
+        assert!(solve_multiple(&a, &ndarray::Array::zeros(vec![3, 1])).is_err());
+    }
+
+    #[test]
+    fn test_kron() {
+        let a = array![[1.0, 2.0], [3.0, 4.0]].into_dyn();
+        let b = array![[0.0, 5.0], [6.0, 7.0]].into_dyn();
+        let expected = array![
+            [0.0, 5.0, 0.0, 10.0],
+            [6.0, 7.0, 12.0, 14.0],
+            [0.0, 15.0, 0.0, 20.0],
+            [18.0, 21.0, 24.0, 28.0]
+        ].into_dyn();
+        assert_eq!(kron(&a, &b), expected);
+        assert_eq!(kron(&a, &array![[1.0, 1.0, 1.0]].into_dyn()).shape(), &[2, 6]);
+    }
+
+    #[test]
+    fn test_expm_known_matrices() {
+        let diagonal = array![[1.0, 0.0], [0.0, -2.0]].into_dyn();
+        let expected = array![[1f64.exp(), 0.0], [0.0, (-2f64).exp()]].into_dyn();
+        assert!(expm(&diagonal).unwrap().abs_diff_eq(&expected, 1e-12));
+
+        let nilpotent = array![[0.0, 3.0], [0.0, 0.0]].into_dyn();
+        assert!(expm(&nilpotent).unwrap().abs_diff_eq(&array![[1.0, 3.0], [0.0, 1.0]].into_dyn(), 1e-12));
+
+        let theta: f64 = 2.5;
+        let rotation = array![[0.0, -theta], [theta, 0.0]].into_dyn();
+        let expected = array![[theta.cos(), -theta.sin()], [theta.sin(), theta.cos()]].into_dyn();
+        assert!(expm(&rotation).unwrap().abs_diff_eq(&expected, 1e-12));
+
+        assert!(expm(&ndarray::Array::zeros(vec![2, 3])).is_err());
+    }
+
+    #[test]
+    fn test_slogdet() {
+        let input = array![[1.0, 2.0, 0.5], [3.0, 4.0, -1.0], [0.0, 2.0, 1.5]].into_dyn();
+        let (sign, logdet) = slogdet(&input);
+        assert!((sign * logdet.exp() - determinant_unwrap(&input)).abs() < 1e-9);
+
+        let scaled = (ndarray::Array2::<f64>::eye(400) * 10.0).into_dyn(); // det = 1e400 overflows f64
+        let (sign, logdet) = slogdet(&scaled);
+        assert_eq!(sign, 1.0);
+        assert!((logdet - 400.0 * 10f64.ln()).abs() < 1e-9);
+
+        assert_eq!(slogdet(&array![[1.0, 2.0], [2.0, 4.0]].into_dyn()), (0.0, f64::NEG_INFINITY));
+    }
+
+    #[test]
+    fn test_block_diag() {
+        let a = array![[1.0, 2.0], [3.0, 4.0]].into_dyn();
+        let b = array![[5.0]].into_dyn();
+        let expected = array![[1.0, 2.0, 0.0], [3.0, 4.0, 0.0], [0.0, 0.0, 5.0]].into_dyn();
+        assert_eq!(block_diag(&[a, b]), expected);
+    }
+
+    #[test]
+    fn test_gram_matrix() {
+        let input = array![[1.0, 2.0, 0.0], [-1.0, 3.0, 2.0]].into_dyn();
+        let gram = gram_matrix(&input);
+        assert_eq!(gram.shape(), &[2, 2]);
+        assert_eq!(gram[[0, 1]], gram[[1, 0]]);
+        assert_eq!(gram, dot_unwrap(&input, &transpose(input.clone(), None)));
+    }
+
+    #[test]
+    fn test_covariance() {
+        // Feature means are 2 and 4; deviations are [-1, 0, 1] and [-2, -1, 3]
+        let input = array![[1.0, 2.0], [2.0, 3.0], [3.0, 7.0]].into_dyn();
+        let expected = array![[1.0, 2.5], [2.5, 7.0]].into_dyn();
+        assert!(covariance(&input, 1.0).abs_diff_eq(&expected, 1e-12));
+    }
+
+    #[test]
+    fn test_correlation() {
+        let input = array![[1.0, 2.0, -1.0], [2.0, 3.0, -3.0], [3.0, 7.0, -4.0], [4.0, 6.0, -8.0]].into_dyn();
+        let corr = correlation(&input);
+        assert_eq!(corr.shape(), &[3, 3]);
+        for i in 0..3 {
+            assert_eq!(corr[[i, i]], 1.0);
+        }
+        assert!(corr.iter().all(|&x| (-1.0..=1.0).contains(&x)));
+        assert!(corr[[0, 2]] < 0.0);
+    }
+
+    #[test]
+    fn test_correlation_zero_variance_feature() {
+        let input = array![[1.0, 5.0], [2.0, 5.0], [3.0, 5.0]].into_dyn();
+        assert_eq!(correlation(&input), array![[1.0, 0.0], [0.0, 1.0]].into_dyn());
+    }
+
+    #[test]
+    fn test_softmax_with_lse() {
+        let input = array![[1.0, 2.0, 3.0], [-1.0, 0.0, 500.0]].into_dyn();
+        for axis in [ndarray::Axis(0), ndarray::Axis(1)] {
+            let (probs, lse) = softmax_with_lse(&input, axis);
+            assert!(lse.abs_diff_eq(&logsumexp_axis(&input, axis), 1e-12));
+            assert!(probs.abs_diff_eq(&softmax_2d(&input, axis), 1e-12));
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        let input = array![1.0, 2.0, 3.0, 4.0, 5.0, 6.0].into_dyn();
+        let parts = split(&input, &[2, 4], ndarray::Axis(0)).unwrap();
+        assert_eq!(parts, vec![array![1.0, 2.0].into_dyn(), array![3.0, 4.0, 5.0, 6.0].into_dyn()]);
+
+        let matrix = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].into_dyn();
+        let columns = split(&matrix, &[1, 2], ndarray::Axis(1)).unwrap();
+        assert_eq!(columns[0], array![[1.0], [4.0]].into_dyn());
+        assert_eq!(columns[1], array![[2.0, 3.0], [5.0, 6.0]].into_dyn());
+    }
+
+    #[test]
+    fn test_split_rejects_bad_sections() {
+        let input = array![1.0, 2.0, 3.0].into_dyn();
+        let err = split(&input, &[1, 1], ndarray::Axis(0)).unwrap_err();
+        assert_eq!(err.kind(), ndarray::ErrorKind::IncompatibleShape);
+        assert_eq!(split(&input, &[3], ndarray::Axis(1)).unwrap_err().kind(), ndarray::ErrorKind::OutOfBounds);
+    }
+
+    #[test]
+    fn test_chunk() {
+        let input = array![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0].into_dyn();
+        let parts = chunk(&input, 3, ndarray::Axis(0));
+        let sizes: Vec<usize> = parts.iter().map(|part| part.len()).collect();
+        assert_eq!(sizes, vec![3, 2, 2]);
+        assert_eq!(parts[1], array![4.0, 5.0].into_dyn());
+    }
+
+    #[test]
+    fn test_masked_mean() {
+        let input = array![[1.0, 2.0, f64::NAN], [4.0, 8.0, 6.0], [5.0, 5.0, 5.0]].into_dyn();
+        let mask = array![[true, true, false], [true, false, true], [false, false, false]].into_dyn();
+        let result = masked_mean(&input, &mask, ndarray::Axis(1));
+        assert_eq!(result, array![(1.0 + 2.0) / 2.0, (4.0 + 6.0) / 2.0, 0.0].into_dyn());
+
+        let by_column = masked_mean(&input, &mask, ndarray::Axis(0));
+        assert_eq!(by_column, array![2.5, 2.0, 6.0].into_dyn());
+    }
+
+    #[test]
+    fn test_fallible_ops_report_shape_errors() {
+        let a = array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].into_dyn();
+        assert_eq!(dot(&a, &a), Err(TensorError::ShapeMismatch { left: vec![2, 3], right: vec![2, 3] }));
+        assert_eq!(determinant(&a), Err(TensorError::NonSquare { rows: 2, cols: 3 }));
+        assert_eq!(determinant(&array![1.0, 2.0].into_dyn()), Err(TensorError::RankMismatch { expected: 2, actual: 1 }));
+        assert_eq!(reshape(a.clone(), &[4]), Err(TensorError::ShapeMismatch { left: vec![2, 3], right: vec![4] }));
+        assert_eq!(softmax(&a), Err(TensorError::RankMismatch { expected: 1, actual: 2 }));
+
+        assert_eq!(reshape(a, &[3, 2]).unwrap().shape(), &[3, 2]);
+        assert!((determinant(&array![[2.0, 1.0], [1.0, 3.0]].into_dyn()).unwrap() - 5.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_dot_dispatches_on_rank() {
+        let v = array![1.0, 2.0, 3.0].into_dyn();
+        let w = array![4.0, -1.0, 0.5].into_dyn();
+        let inner = dot_unwrap(&v, &w);
+        assert_eq!(inner.ndim(), 0);
+        assert_eq!(inner[[]], 3.5);
+
+        let m = array![[1.0, 0.0, 2.0], [0.0, 1.0, -1.0]].into_dyn();
+        assert_eq!(dot_unwrap(&m, &v), array![7.0, -1.0].into_dyn());
+        assert_eq!(dot_unwrap(&array![1.0, 2.0].into_dyn(), &m), array![1.0, 2.0, 0.0].into_dyn());
+
+        let batch1 = array![[[1.0, 2.0], [3.0, 4.0]], [[0.0, 1.0], [1.0, 0.0]]].into_dyn();
+        let batch2 = array![[[1.0], [1.0]], [[2.0], [3.0]]].into_dyn();
+        assert_eq!(dot_unwrap(&batch1, &batch2), array![[[3.0], [7.0]], [[3.0], [2.0]]].into_dyn());
+
+        assert_eq!(dot(&v, &array![1.0, 2.0].into_dyn()), Err(TensorError::ShapeMismatch { left: vec![3], right: vec![2] }));
+        assert!(matches!(dot(&batch1, &batch1.slice(ndarray::s![..1, .., ..]).to_owned().into_dyn()), Err(TensorError::ShapeMismatch { .. })));
+    }
+
+    #[test]
+    fn test_inverse() {
+        let a = array![[4.0, 7.0], [2.0, 6.0]].into_dyn();
+        let a_inv = inverse(&a).unwrap();
+        assert!(dot_unwrap(&a, &a_inv).abs_diff_eq(&ndarray::Array2::<f64>::eye(2).into_dyn(), 1e-12));
+
+        assert_eq!(inverse(&array![[1.0, 2.0], [2.0, 4.0]].into_dyn()), Err(TensorError::NotInvertible));
+        assert_eq!(inverse(&array![[1.0, 2.0, 3.0]].into_dyn()), Err(TensorError::NonSquare { rows: 1, cols: 3 }));
+    }
+
+    #[test]
+    fn test_solve() {
+        let a = array![[3.0, 1.0, -1.0], [1.0, 4.0, 2.0], [0.0, 2.0, 5.0]].into_dyn();
+        let b = array![2.0, -1.0, 4.0].into_dyn();
+        let x = solve(&a, &b);
+        assert_eq!(x.shape(), &[3]);
+        assert!(dot_unwrap(&a, &x).abs_diff_eq(&b, 1e-12));
+
+        let rhs = array![[1.0, 0.0], [0.0, 1.0], [2.0, -3.0]].into_dyn();
+        let x = solve(&a, &rhs);
+        assert_eq!(x.shape(), &[3, 2]);
+        assert!(dot_unwrap(&a, &x).abs_diff_eq(&rhs, 1e-12));
+    }
+
+    #[test]
+    fn test_try_solve_errors() {
+        let singular = array![[1.0, 2.0], [2.0, 4.0]].into_dyn();
+        assert_eq!(try_solve(&singular, &array![1.0, 1.0].into_dyn()), Err(TensorError::NotInvertible));
+        let square = array![[1.0, 0.0], [0.0, 1.0]].into_dyn();
+        assert_eq!(
+            try_solve(&square, &array![1.0, 2.0, 3.0].into_dyn()),
+            Err(TensorError::ShapeMismatch { left: vec![2, 2], right: vec![3] })
+        );
+        assert_eq!(try_solve(&array![[1.0, 2.0]].into_dyn(), &array![1.0].into_dyn()), Err(TensorError::NonSquare { rows: 1, cols: 2 }));
+    }
+
+    #[test]
+    fn test_svd_reconstructs() {
+        let a = array![[3.0, 1.0, 2.0], [-1.0, 4.0, 0.5]].into_dyn();
+
+        let (u, s, vt) = svd(&a, false);
+        assert_eq!((u.shape(), s.len(), vt.shape()), (&[2, 2][..], 2, &[2, 3][..]));
+        assert!(s[0] >= s[1]);
+        let sigma = ndarray::Array2::from_diag(&s).into_dyn();
+        assert!(dot_unwrap(&dot_unwrap(&u, &sigma), &vt).abs_diff_eq(&a, 1e-10));
+
+        let (u, s, vt) = svd(&a, true);
+        assert_eq!((u.shape(), vt.shape()), (&[2, 2][..], &[3, 3][..]));
+        let mut sigma = ndarray::Array2::<f64>::zeros((2, 3));
+        sigma.diag_mut().assign(&s);
+        assert!(dot_unwrap(&dot_unwrap(&u, &sigma.into_dyn()), &vt).abs_diff_eq(&a, 1e-10));
+        assert!(dot_unwrap(&transpose(vt.clone(), None), &vt).abs_diff_eq(&ndarray::Array2::<f64>::eye(3).into_dyn(), 1e-10));
+    }
+
+    #[test]
+    fn test_eigh() {
+        let a = array![[2.0, 1.0], [1.0, 2.0]].into_dyn();
+        let (values, vectors) = eigh(&a);
+        assert!(values.abs_diff_eq(&array![1.0, 3.0], 1e-12));
+        for (k, &value) in values.iter().enumerate() {
+            let v = vectors.index_axis(ndarray::Axis(1), k).to_owned().into_dyn();
+            assert!(dot_unwrap(&a, &v).abs_diff_eq(&(&v * value), 1e-12));
+        }
+
+        assert_eq!(try_eigh(&array![[1.0, 2.0], [0.0, 1.0]].into_dyn()), Err(TensorError::NotSymmetric));
+        assert_eq!(try_eigh(&array![[1.0, 2.0]].into_dyn()), Err(TensorError::NonSquare { rows: 1, cols: 2 }));
+    }
+
+    #[test]
+    fn test_load_tensor_rejects_corrupt_header() {
+        let path = std::env::temp_dir().join(format!("rax_corrupt_tensor_{}.bin", std::process::id()));
+        save_tensor(&array![[1.0, 2.0], [3.0, 4.0]].into_dyn(), &path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+
+        // Truncated data
+        std::fs::write(&path, &bytes[..bytes.len() - 8]).unwrap();
+        assert_eq!(load_tensor(&path).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+        // Truncated header
+        std::fs::write(&path, &bytes[..6]).unwrap();
+        assert_eq!(load_tensor(&path).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+        // Absurd rank
+        let mut huge_rank = bytes.clone();
+        huge_rank[4..12].copy_from_slice(&u64::MAX.to_le_bytes());
+        std::fs::write(&path, &huge_rank).unwrap();
+        assert_eq!(load_tensor(&path).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+        // Dimensions whose product overflows
+        let mut huge_dims = bytes.clone();
+        huge_dims[12..20].copy_from_slice(&u64::MAX.to_le_bytes());
+        huge_dims[20..28].copy_from_slice(&u64::MAX.to_le_bytes());
+        std::fs::write(&path, &huge_dims).unwrap();
+        assert_eq!(load_tensor(&path).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+        // Large but representable length with no data behind it
+        let mut huge_len = bytes.clone();
+        huge_len[12..20].copy_from_slice(&(1u64 << 40).to_le_bytes());
+        std::fs::write(&path, &huge_len).unwrap();
+        assert_eq!(load_tensor(&path).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "rms_norm needs an input with at least one axis")]
+    fn test_rms_norm_rejects_scalar_input() {
+        rms_norm(&ndarray::arr0(2.0).into_dyn(), &array![1.0].into_dyn(), 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "rms_norm gamma must have shape [4], got [3]")]
+    fn test_rms_norm_rejects_mismatched_gamma() {
+        rms_norm(&ndarray::Array::ones(vec![2, 4]), &array![1.0, 1.0, 1.0].into_dyn(), 1e-12);
+    }
+}